@@ -0,0 +1,21 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use solvent::DepGraph;
+
+// Every iterator takes its own copy of the satisfied set, so creating one costs in
+// proportion to the size of that set. Half the nodes of each graph are satisfied.
+fn iterator_setup(c: &mut Criterion) {
+    let mut group = c.benchmark_group("iterator_setup");
+    for nodes in [1_000, 10_000, 100_000] {
+        let mut depgraph: DepGraph<usize> = DepGraph::new();
+        depgraph.register_nodes((0..nodes).collect());
+        let satisfied: Vec<usize> = (0..nodes).filter(|n| n % 2 == 0).collect();
+        depgraph.mark_as_satisfied(&satisfied).unwrap();
+        group.bench_with_input(BenchmarkId::from_parameter(nodes), &depgraph, |b, g| {
+            b.iter(|| g.dependencies_of(black_box(&1)).unwrap().next())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, iterator_setup);
+criterion_main!(benches);
@@ -0,0 +1,32 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use solvent::DepGraph;
+
+// A root depending upon `fan_out` nodes, each of which depends upon the same `fan_out`
+// leaves
+fn layered_graph(fan_out: usize) -> DepGraph<usize> {
+    let mut depgraph: DepGraph<usize> = DepGraph::new();
+    let mids: Vec<usize> = (1..=fan_out).collect();
+    let leaves: Vec<usize> = (fan_out + 1..=2 * fan_out).collect();
+    depgraph.register_dependencies(0, mids.clone());
+    for mid in mids {
+        depgraph.register_dependencies(mid, leaves.clone());
+    }
+    depgraph
+}
+
+// Dependency sets of up to 8 nodes are stored inline and searched linearly, while larger
+// ones are hashed as every set was before. Resolving with fan-outs either side of that
+// threshold compares the two.
+fn small_graph_resolution(c: &mut Criterion) {
+    let mut group = c.benchmark_group("small_graph_resolution");
+    for fan_out in [2, 4, 8, 9, 12] {
+        let depgraph = layered_graph(fan_out);
+        group.bench_with_input(BenchmarkId::from_parameter(fan_out), &depgraph, |b, g| {
+            b.iter(|| g.resolve(black_box(&0)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, small_graph_resolution);
+criterion_main!(benches);
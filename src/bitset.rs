@@ -0,0 +1,115 @@
+use core::iter::FromIterator;
+
+use alloc::vec::Vec;
+
+const WORD_BITS: usize = 64;
+
+// A set of node positions, stored as one bit per position. Positions are small and
+// dense, so this takes far less memory than a HashSet and is much cheaper to clone,
+// which matters as every iterator takes its own copy of the satisfied set.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    pub(crate) fn new() -> BitSet {
+        BitSet::default()
+    }
+
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    #[cfg(test)]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.words.iter().all(|w| *w == 0)
+    }
+
+    #[cfg(test)]
+    pub(crate) fn word_count(&self) -> usize {
+        self.words.len()
+    }
+
+    pub(crate) fn contains(&self, pos: &usize) -> bool {
+        self.words
+            .get(pos / WORD_BITS)
+            .map_or(false, |w| w & (1 << (pos % WORD_BITS)) != 0)
+    }
+
+    // Insert a member, returning false if it was already present
+    pub(crate) fn insert(&mut self, pos: usize) -> bool {
+        let word = pos / WORD_BITS;
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        let bit = 1 << (pos % WORD_BITS);
+        let absent = self.words[word] & bit == 0;
+        self.words[word] |= bit;
+        absent
+    }
+
+    // The members, in ascending order
+    pub(crate) fn iter(&self) -> Iter<'_> {
+        Iter {
+            words: &self.words,
+            word: 0,
+            bits: self.words.first().cloned().unwrap_or(0),
+        }
+    }
+}
+
+pub(crate) struct Iter<'a> {
+    words: &'a [u64],
+    // The index of the word being iterated over, and its bits not yet returned
+    word: usize,
+    bits: u64,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.bits == 0 {
+            self.word += 1;
+            self.bits = *self.words.get(self.word)?;
+        }
+        let bit = self.bits.trailing_zeros() as usize;
+        self.bits &= self.bits - 1;
+        Some(self.word * WORD_BITS + bit)
+    }
+}
+
+impl PartialEq for BitSet {
+    fn eq(&self, other: &BitSet) -> bool {
+        // Trailing empty words do not affect membership
+        let len = self.words.len().max(other.words.len());
+        (0..len).all(|i| self.words.get(i).unwrap_or(&0) == other.words.get(i).unwrap_or(&0))
+    }
+}
+
+impl<'a> IntoIterator for &'a BitSet {
+    type Item = usize;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+impl Extend<usize> for BitSet {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        for pos in iter {
+            self.insert(pos);
+        }
+    }
+}
+
+impl FromIterator<usize> for BitSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> BitSet {
+        let mut set = BitSet::new();
+        set.extend(iter);
+        set
+    }
+}
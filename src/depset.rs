@@ -0,0 +1,159 @@
+use core::iter::FromIterator;
+
+use smallvec::SmallVec;
+
+use crate::HashSet;
+
+// Dependency sets with up to this many members are stored inline and searched linearly
+const INLINE_CAPACITY: usize = 8;
+
+// The set of dependencies of a single node. Most nodes have only a handful of
+// dependencies, for which hashing costs more than it saves, so small sets are kept in
+// an inline array and only switch over to a HashSet once they outgrow it.
+#[derive(Debug, Clone)]
+pub(crate) enum DepSet {
+    Small(SmallVec<[usize; INLINE_CAPACITY]>),
+    Large(HashSet<usize>),
+}
+
+impl Default for DepSet {
+    fn default() -> Self {
+        DepSet::Small(SmallVec::new())
+    }
+}
+
+impl DepSet {
+    pub(crate) fn with_capacity(capacity: usize) -> DepSet {
+        if capacity > INLINE_CAPACITY {
+            DepSet::Large(HashSet::with_capacity(capacity))
+        } else {
+            DepSet::default()
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            DepSet::Small(items) => items.len(),
+            DepSet::Large(set) => set.len(),
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub(crate) fn contains(&self, pos: &usize) -> bool {
+        match self {
+            DepSet::Small(items) => items.contains(pos),
+            DepSet::Large(set) => set.contains(pos),
+        }
+    }
+
+    // Insert a member, returning false if it was already present
+    pub(crate) fn insert(&mut self, pos: usize) -> bool {
+        match self {
+            DepSet::Small(items) => {
+                if items.contains(&pos) {
+                    return false;
+                }
+                if items.len() < INLINE_CAPACITY {
+                    items.push(pos);
+                } else {
+                    let mut set: HashSet<usize> = items.drain(..).collect();
+                    set.insert(pos);
+                    *self = DepSet::Large(set);
+                }
+                true
+            }
+            DepSet::Large(set) => set.insert(pos),
+        }
+    }
+
+    // Remove a member, returning false if it was not present
+    pub(crate) fn remove(&mut self, pos: &usize) -> bool {
+        match self {
+            DepSet::Small(items) => match items.iter().position(|n| n == pos) {
+                Some(i) => {
+                    items.remove(i);
+                    true
+                }
+                None => false,
+            },
+            DepSet::Large(set) => set.remove(pos),
+        }
+    }
+
+    // Remove every member, keeping the allocated capacity
+    pub(crate) fn clear(&mut self) {
+        match self {
+            DepSet::Small(items) => items.clear(),
+            DepSet::Large(set) => set.clear(),
+        }
+    }
+
+    pub(crate) fn iter(&self) -> Iter<'_> {
+        match self {
+            DepSet::Small(items) => Iter::Small(items.iter()),
+            DepSet::Large(set) => Iter::Large(set.iter()),
+        }
+    }
+}
+
+pub(crate) enum Iter<'a> {
+    Small(core::slice::Iter<'a, usize>),
+    Large(<&'a HashSet<usize> as IntoIterator>::IntoIter),
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = &'a usize;
+
+    fn next(&mut self) -> Option<&'a usize> {
+        match self {
+            Iter::Small(iter) => iter.next(),
+            Iter::Large(iter) => iter.next(),
+        }
+    }
+}
+
+impl PartialEq for DepSet {
+    fn eq(&self, other: &DepSet) -> bool {
+        self.len() == other.len() && self.iter().all(|pos| other.contains(pos))
+    }
+}
+
+impl<'a> IntoIterator for &'a DepSet {
+    type Item = &'a usize;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+impl IntoIterator for DepSet {
+    type Item = usize;
+    type IntoIter = alloc::vec::IntoIter<usize>;
+
+    fn into_iter(self) -> alloc::vec::IntoIter<usize> {
+        match self {
+            DepSet::Small(items) => items.into_vec().into_iter(),
+            DepSet::Large(set) => set.into_iter().collect::<alloc::vec::Vec<_>>().into_iter(),
+        }
+    }
+}
+
+impl Extend<usize> for DepSet {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        for pos in iter {
+            self.insert(pos);
+        }
+    }
+}
+
+impl FromIterator<usize> for DepSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> DepSet {
+        let mut set = DepSet::default();
+        set.extend(iter);
+        set
+    }
+}
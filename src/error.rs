@@ -1,11 +1,30 @@
+use core::fmt;
+#[cfg(feature = "std")]
 use std::error::Error;
-use std::fmt;
+
+use alloc::string::String;
+use alloc::vec::Vec;
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum SolventError {
     /// A cycle has been detected
     CycleDetected(String),
     NoSuchNode,
+    /// The same node was given more than once where nodes must be unique
+    DuplicateNode,
+    /// A line of input could not be parsed (line number, starting at 1, and description)
+    ParseError(usize, String),
+    /// Nodes which were only ever referenced as dependencies, never registered
+    PhantomNodes(Vec<String>),
+    /// The graph could not be serialized or deserialized
+    Serialization(String),
+    /// A node is satisfied, or was to be marked satisfied, while one of its dependencies
+    /// is not (the node, and the unsatisfied dependency)
+    DependenciesNotSatisfied(String, String),
+    /// More dependencies were given than allowed (the number given, and the maximum)
+    TooManyDependencies(usize, usize),
+    /// The graph's internal state is inconsistent (a description of the first problem found)
+    CorruptGraph(String),
 }
 
 impl fmt::Display for SolventError {
@@ -13,8 +32,75 @@ impl fmt::Display for SolventError {
         match *self {
             SolventError::CycleDetected(ref s) => write!(f, "Cycle Detected: {}", s),
             SolventError::NoSuchNode => write!(f, "No Such Node"),
+            SolventError::DuplicateNode => write!(f, "Duplicate Node"),
+            SolventError::ParseError(line, ref s) => {
+                write!(f, "Parse Error at line {}: {}", line, s)
+            }
+            SolventError::PhantomNodes(ref nodes) => {
+                write!(f, "Phantom Nodes: {}", nodes.join(", "))
+            }
+            SolventError::Serialization(ref s) => write!(f, "Serialization Error: {}", s),
+            SolventError::DependenciesNotSatisfied(ref node, ref dep) => {
+                write!(f, "Dependencies Not Satisfied: {} requires {}", node, dep)
+            }
+            SolventError::TooManyDependencies(count, max) => {
+                write!(
+                    f,
+                    "Too Many Dependencies: {} given, at most {} allowed",
+                    count, max
+                )
+            }
+            SolventError::CorruptGraph(ref s) => write!(f, "Corrupt Graph: {}", s),
         }
     }
 }
 
+#[cfg(feature = "std")]
 impl Error for SolventError {}
+
+#[cfg(feature = "std")]
+impl From<SolventError> for std::io::Error {
+    fn from(e: SolventError) -> std::io::Error {
+        use std::io::ErrorKind;
+        let kind = match e {
+            SolventError::NoSuchNode | SolventError::PhantomNodes(_) => ErrorKind::NotFound,
+            SolventError::DuplicateNode => ErrorKind::AlreadyExists,
+            SolventError::DependenciesNotSatisfied(_, _) => ErrorKind::Other,
+            SolventError::TooManyDependencies(_, _) => ErrorKind::InvalidInput,
+            SolventError::CycleDetected(_)
+            | SolventError::ParseError(_, _)
+            | SolventError::Serialization(_)
+            | SolventError::CorruptGraph(_) => ErrorKind::InvalidData,
+        };
+        std::io::Error::new(kind, e)
+    }
+}
+
+/// The error returned by `DepGraph::for_each_dependency`, distinguishing errors from the
+/// resolver from errors returned by the caller's closure.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ResolveOrUser<E> {
+    /// The resolver failed
+    Resolve(SolventError),
+    /// The closure returned an error
+    User(E),
+}
+
+impl<E: fmt::Display> fmt::Display for ResolveOrUser<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ResolveOrUser::Resolve(ref e) => write!(f, "{}", e),
+            ResolveOrUser::User(ref e) => write!(f, "{}", e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: Error + 'static> Error for ResolveOrUser<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            ResolveOrUser::Resolve(ref e) => Some(e),
+            ResolveOrUser::User(ref e) => Some(e),
+        }
+    }
+}
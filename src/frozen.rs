@@ -0,0 +1,178 @@
+use alloc::format;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::hash::{Hash, Hasher};
+
+use crate::{apply_hints, FnvHasher, HashMap, SolventError};
+
+/// An immutable dependency graph, produced by `DepGraph::freeze()`. Dependencies are
+/// stored in flat arrays rather than per-node sets, and nodes are found through a
+/// prebuilt index, which makes repeated resolution faster. A FrozenDepGraph cannot be
+/// modified.
+#[derive(Debug, Clone)]
+pub struct FrozenDepGraph<T: Eq> {
+    // The nodes in the graph, in the same positions as in the DepGraph
+    nodes: Vec<T>,
+
+    // The dependencies of node n are dep_targets[dep_offsets[n]..dep_offsets[n + 1]]
+    dep_offsets: Vec<usize>,
+    dep_targets: Vec<usize>,
+
+    // The dependents of node n are rev_targets[rev_offsets[n]..rev_offsets[n + 1]]
+    rev_offsets: Vec<usize>,
+    rev_targets: Vec<usize>,
+
+    // Whether each node is satisfied
+    satisfied: Vec<bool>,
+
+    // Node positions by the hash of the node
+    index: HashMap<u64, Vec<usize>>,
+
+    // Alternative names for nodes, with the position of the node providing each
+    aliases: Vec<(T, usize)>,
+
+    // Soft ordering preferences, as pairs of positions with the first preferred first
+    hints: Vec<(usize, usize)>,
+}
+
+impl<T: Eq + Hash> FrozenDepGraph<T> {
+    pub(crate) fn new(
+        nodes: Vec<T>,
+        dependencies: Vec<Vec<usize>>,
+        satisfied: Vec<bool>,
+        aliases: Vec<(T, usize)>,
+        hints: Vec<(usize, usize)>,
+    ) -> FrozenDepGraph<T> {
+        let mut dep_offsets = Vec::with_capacity(nodes.len() + 1);
+        let mut dep_targets = Vec::new();
+        let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); nodes.len()];
+        for (n, deplist) in dependencies.into_iter().enumerate() {
+            dep_offsets.push(dep_targets.len());
+            for dep in deplist.into_iter() {
+                dependents[dep].push(n);
+                dep_targets.push(dep);
+            }
+        }
+        dep_offsets.push(dep_targets.len());
+
+        let mut rev_offsets = Vec::with_capacity(nodes.len() + 1);
+        let mut rev_targets = Vec::new();
+        for deplist in dependents.into_iter() {
+            rev_offsets.push(rev_targets.len());
+            rev_targets.extend(deplist);
+        }
+        rev_offsets.push(rev_targets.len());
+
+        let mut index: HashMap<u64, Vec<usize>> = HashMap::new();
+        for (n, node) in nodes.iter().enumerate() {
+            index.entry(hash_of(node)).or_default().push(n);
+        }
+
+        FrozenDepGraph {
+            nodes,
+            dep_offsets,
+            dep_targets,
+            rev_offsets,
+            rev_targets,
+            satisfied,
+            index,
+            aliases,
+            hints,
+        }
+    }
+
+    fn _pos(&self, node: &T) -> Option<usize> {
+        self.index
+            .get(&hash_of(node))
+            .and_then(|poses| poses.iter().cloned().find(|n| self.nodes[*n] == *node))
+            .or_else(|| {
+                self.aliases
+                    .iter()
+                    .find(|(alias, _)| alias == node)
+                    .map(|(_, pos)| *pos)
+            })
+    }
+
+    fn _deps(&self, pos: usize) -> &[usize] {
+        &self.dep_targets[self.dep_offsets[pos]..self.dep_offsets[pos + 1]]
+    }
+
+    /// Resolve the dependencies of the target node, as `DepGraph::resolve` does. Target
+    /// node must exist.
+    pub fn resolve<'a>(&'a self, target: &T) -> Result<Vec<&'a T>, SolventError> {
+        let pos = self._pos(target).ok_or(SolventError::NoSuchNode)?;
+        let mut visited = vec![false; self.nodes.len()];
+        let mut order = Vec::new();
+        self._visit(pos, &mut visited, &mut order)?;
+        if !self.hints.is_empty() {
+            order = apply_hints(order, &self.hints, &|n| self._deps(n).to_vec());
+        }
+        Ok(order.into_iter().map(|n| &self.nodes[n]).collect())
+    }
+
+    /// Get the nodes which directly depend upon the target node. Target node must exist.
+    pub fn dependents_of<'a>(&'a self, target: &T) -> Result<Vec<&'a T>, SolventError> {
+        let pos = self._pos(target).ok_or(SolventError::NoSuchNode)?;
+        Ok(
+            self.rev_targets[self.rev_offsets[pos]..self.rev_offsets[pos + 1]]
+                .iter()
+                .map(|n| &self.nodes[*n])
+                .collect(),
+        )
+    }
+
+    /// Get all of the unsatisfied nodes in the graph, as `DepGraph::topological_sort`
+    /// does.
+    pub fn topological_sort(&self) -> Result<Vec<&T>, SolventError> {
+        let mut visited = vec![false; self.nodes.len()];
+        let mut order = Vec::new();
+        for pos in 0..self.nodes.len() {
+            self._visit(pos, &mut visited, &mut order)?;
+        }
+        Ok(order.into_iter().map(|n| &self.nodes[n]).collect())
+    }
+
+    // Depth first postorder walk of the unsatisfied dependencies of `pos`, in the same
+    // order as `DepGraph` walks them, but without recursion.
+    fn _visit(
+        &self,
+        pos: usize,
+        visited: &mut [bool],
+        order: &mut Vec<usize>,
+    ) -> Result<(), SolventError> {
+        if visited[pos] || self.satisfied[pos] {
+            return Ok(());
+        }
+
+        // The current path, with how many dependencies of each node have been walked
+        let mut curpath: Vec<(usize, usize)> = vec![(pos, 0)];
+        while let Some((n, i)) = curpath.last_mut() {
+            let n = *n;
+            match self._deps(n).get(*i) {
+                Some(dep) => {
+                    *i += 1;
+                    let dep = *dep;
+                    if curpath.iter().any(|(p, _)| *p == dep) {
+                        let path: Vec<usize> = curpath.iter().map(|(p, _)| *p).collect();
+                        return Err(SolventError::CycleDetected(format!("{:?}", path)));
+                    }
+                    if !visited[dep] && !self.satisfied[dep] {
+                        curpath.push((dep, 0));
+                    }
+                }
+                None => {
+                    curpath.pop();
+                    visited[n] = true;
+                    order.push(n);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn hash_of<T: Hash>(node: &T) -> u64 {
+    let mut hasher = FnvHasher::default();
+    node.hash(&mut hasher);
+    hasher.finish()
+}
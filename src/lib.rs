@@ -40,6 +40,7 @@
 //! The above will output:  `d b e c a` or `e c d b a` or some other valid dependency order.
 //!
 //! The algorithm is not deterministic, and may give a different answer each time it is run. Beware.
+//! Enable the `deterministic` feature if the same graph must always resolve the same way.
 //!
 //! The iterator dependencies_of() returns an `Option<Result<T ,SolventError>>`.  The for loop
 //! handles the `Option` part for you, but you may want to check the result for `SolventError`s.
@@ -53,16 +54,52 @@
 //! ```
 //!
 //! Dependency cycles are detected and will return `SolventError::CycleDetected`.
+//!
+//! Solvent uses `std` by default. It can also be used in `no_std` environments which
+//! provide `alloc` by disabling default features and enabling the `alloc` feature, in
+//! which case the `hashbrown` collections are used.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(any(feature = "std", feature = "alloc")))]
+compile_error!("solvent requires either the `std` or the `alloc` feature");
+
+extern crate alloc;
 
 pub mod error;
-pub use error::SolventError;
+pub use error::{ResolveOrUser, SolventError};
+
+mod bitset;
+use bitset::BitSet;
+
+mod depset;
+use depset::DepSet;
+
+mod frozen;
+pub use frozen::FrozenDepGraph;
+
+#[cfg(feature = "serde")]
+mod serialize;
 
+#[cfg(not(feature = "std"))]
+use hashbrown::{HashMap, HashSet};
 #[cfg(feature = "deterministic")]
 use indexmap::{map::IndexMap as HashMap, set::IndexSet as HashSet};
-#[cfg(not(feature = "deterministic"))]
+#[cfg(all(feature = "std", not(feature = "deterministic")))]
 use std::collections::{HashMap, HashSet};
 
-use std::iter::Iterator;
+use alloc::collections::{BTreeMap, BTreeSet, VecDeque};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt::{Debug, Display};
+use core::hash::{Hash, Hasher};
+use core::iter::Iterator;
+
+/// The index of a node within a DepGraph, as used by `node_at` and `resolve_indices`.
+pub type NodeId = usize;
 
 /// This is the dependency graph. The type `T` is intended to be a small type, or a
 /// reference to a larger type that implements `Eq` (you will need to supply the type
@@ -75,10 +112,30 @@ pub struct DepGraph<T: Eq> {
     // List of dependencies. The first node depends on the set of additional nodes.
     // We store indices into the nodes array.  This way we can have Eq + Copy + Hash
     // without any requirements on type T.
-    dependencies: HashMap<usize, HashSet<usize>>,
+    dependencies: HashMap<usize, DepSet>,
 
     // The set of nodes already satisfied (by index into the nodes array).
-    satisfied: HashSet<usize>,
+    satisfied: BitSet,
+
+    // Tags attached to nodes (by index into the nodes array).
+    tags: HashMap<usize, HashSet<String>>,
+
+    // The set of nodes registered in their own right, rather than only referenced as a
+    // dependency of another node (by index into the nodes array).
+    declared: HashSet<usize>,
+
+    // A topological order of the nodes, maintained only for graphs created with
+    // `new_acyclic`.
+    acyclic: Option<TopoOrder>,
+
+    // Alternative names for nodes (see `register_provides`), each with the index into the
+    // nodes array of the node providing it.
+    aliases: Vec<(T, usize)>,
+
+    // Soft ordering preferences (see `register_hint`), as pairs of nodes with the first
+    // preferred before the second. Nodes need not be registered, so these are only
+    // looked up when resolving.
+    hints: Vec<(T, T)>,
 }
 
 impl<T: Eq> Default for DepGraph<T> {
@@ -86,8 +143,29 @@ impl<T: Eq> Default for DepGraph<T> {
         Self {
             nodes: Vec::new(),
             dependencies: HashMap::new(),
-            satisfied: HashSet::new(),
+            satisfied: BitSet::new(),
+            tags: HashMap::new(),
+            declared: HashSet::new(),
+            acyclic: None,
+            aliases: Vec::new(),
+            hints: Vec::new(),
+        }
+    }
+}
+
+/// Build a DepGraph from a map of each node to its dependencies. The keys are registered
+/// first, in sorted order, so that the index of each key (see `node_at`) is its position
+/// amongst the keys. Dependencies which are not keys follow.
+impl<T: Eq + Ord + Clone> From<BTreeMap<T, Vec<T>>> for DepGraph<T> {
+    fn from(map: BTreeMap<T, Vec<T>>) -> Self {
+        let mut depgraph = DepGraph::new();
+        for node in map.keys() {
+            depgraph.register_node(node.clone());
         }
+        for (node, depends_on) in map.into_iter() {
+            depgraph.register_dependencies(node, depends_on);
+        }
+        depgraph
     }
 }
 
@@ -97,8 +175,81 @@ impl<T: Eq> DepGraph<T> {
         Self::default()
     }
 
+    /// Create an empty DepGraph which is kept free of cycles. A topological order of the
+    /// nodes is maintained as dependencies are registered (after Pearce and Kelly), so
+    /// checking whether a new dependency would create a cycle only needs to search the
+    /// nodes ordered between the two, rather than the whole graph.
+    ///
+    /// `try_register_dependency`, `try_register_dependencies` and the other fallible
+    /// methods return `SolventError::CycleDetected` if a dependency would create a cycle.
+    /// The infallible methods which register dependencies panic instead. Either way, every
+    /// dependency given is checked before any is registered, so the graph is unchanged.
+    pub fn new_acyclic() -> DepGraph<T> {
+        DepGraph {
+            acyclic: Some(TopoOrder::default()),
+            ..Self::default()
+        }
+    }
+
+    /// Create a DepGraph from its nodes and the `(node_index, dependency_index)` pairs of
+    /// its dependencies, where indices refer to positions in `nodes` (as returned by
+    /// `edge_index_list`). Returns `SolventError::NoSuchNode` if an index is out of range,
+    /// or `SolventError::DuplicateNode` if a node appears more than once.
+    pub fn from_node_and_edge_indices(
+        nodes: Vec<T>,
+        edges: Vec<(usize, usize)>,
+    ) -> Result<DepGraph<T>, SolventError> {
+        let mut depgraph = DepGraph::new();
+        for node in nodes.into_iter() {
+            if depgraph._pos(&node).is_some() {
+                return Err(SolventError::DuplicateNode);
+            }
+            depgraph._declare_node(node);
+        }
+        for (node_pos, dep_pos) in edges.into_iter() {
+            if node_pos >= depgraph.nodes.len() || dep_pos >= depgraph.nodes.len() {
+                return Err(SolventError::NoSuchNode);
+            }
+            depgraph._register_dependency(node_pos, dep_pos);
+        }
+        Ok(depgraph)
+    }
+
+    /// Get every dependency as a `(node_index, dependency_index)` pair, sorted. Indices
+    /// are as for `node_at`.
+    pub fn edge_index_list(&self) -> Vec<(usize, usize)> {
+        let mut edges: Vec<(usize, usize)> = self
+            .dependencies
+            .iter()
+            .flat_map(|(n, deplist)| deplist.iter().map(move |d| (*n, *d)))
+            .collect();
+        edges.sort_unstable();
+        edges
+    }
+
+    /// Get the direct dependencies of every node in the graph, paired with each node.
+    /// Nodes, and the dependencies of each, are listed in the order they were registered.
+    /// Nodes without dependencies have an empty Vec. Satisfied nodes are included. This
+    /// is a Vec of pairs rather than a HashMap because the HashMap in use depends upon the
+    /// `alloc` feature.
+    pub fn adjacency(&self) -> Vec<(&T, Vec<&T>)> {
+        (0..self.nodes.len())
+            .map(|n| {
+                let mut deps: Vec<usize> = self._deps_of(n).collect();
+                deps.sort_unstable();
+                let deps = deps.into_iter().map(|dep| &self.nodes[dep]).collect();
+                (&self.nodes[n], deps)
+            })
+            .collect()
+    }
+
     fn _pos(&self, node: &T) -> Option<usize> {
-        self.nodes.iter().position(|x| x == node)
+        self.nodes.iter().position(|x| x == node).or_else(|| {
+            self.aliases
+                .iter()
+                .find(|(alias, _)| alias == node)
+                .map(|(_, pos)| *pos)
+        })
     }
 
     fn _register_node(&mut self, node: T) -> usize {
@@ -106,11 +257,23 @@ impl<T: Eq> DepGraph<T> {
             Some(pos) => pos,
             None => {
                 self.nodes.push(node);
-                self.nodes.len() - 1
+                let pos = self.nodes.len() - 1;
+                if let Some(order) = self.acyclic.as_mut() {
+                    order.push(pos);
+                }
+                pos
             }
         }
     }
 
+    // Register a node as being in the graph in its own right, rather than only being
+    // referenced as a dependency of some other node.
+    fn _declare_node(&mut self, node: T) -> usize {
+        let pos = self._register_node(node);
+        self.declared.insert(pos);
+        pos
+    }
+
     /// Register nodes in the graph. The `nodes` are added to any existing nodes,
     /// after checking to avoid duplicates.
     pub fn register_nodes(&mut self, mut nodes: Vec<T>) {
@@ -122,283 +285,6065 @@ impl<T: Eq> DepGraph<T> {
     /// Register a node in the graph. The `node` is added to any existing nodes,
     /// after checking to avoid duplicates.
     pub fn register_node(&mut self, node: T) {
-        self._register_node(node);
+        self._declare_node(node);
+    }
+
+    /// Register a node, as `register_node` does, returning the graph so that calls can be
+    /// chained when building it:
+    ///
+    /// ```
+    /// # use solvent::DepGraph;
+    /// let depgraph: DepGraph<&str> = DepGraph::new().with_node("a").with_nodes(vec!["b", "c"]);
+    /// ```
+    pub fn with_node(mut self, node: T) -> Self {
+        self.register_node(node);
+        self
+    }
+
+    /// Register nodes, as `register_nodes` does, returning the graph so that calls can be
+    /// chained when building it.
+    pub fn with_nodes(mut self, nodes: Vec<T>) -> Self {
+        self.register_nodes(nodes);
+        self
     }
 
     /// Add a dependency to a DepGraph. The node does not need to pre-exist, nor does the
     /// dependency node. If the node does pre-exist, the depends_on will be added to
     /// its existing dependency list. Otherwise it will be created.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the graph was created with `new_acyclic` and the dependency would create
+    /// a cycle. Use `try_register_dependency` to handle this as an error.
     pub fn register_dependency(&mut self, node: T, depends_on: T) {
-        let node_pos = self._register_node(node);
+        if let Err(e) =
+            self._check_dependencies(&node, core::slice::from_ref(&depends_on), &mut Vec::new())
+        {
+            panic!("Dependency would create a cycle: {}", e);
+        }
+        let node_pos = self._declare_node(node);
         let dep_pos = self._register_node(depends_on);
+        self._register_dependency(node_pos, dep_pos);
+    }
+
+    // Add the dependency, returning false if it was already registered. If the graph is
+    // kept acyclic, the dependency must already have been checked (see
+    // `_check_dependencies`).
+    fn _register_dependency(&mut self, node_pos: usize, dep_pos: usize) -> bool {
+        if let Some(order) = self.acyclic.as_mut() {
+            if let Err(cycle) =
+                order.add_dependency(&self.dependencies, node_pos, dep_pos, &mut Vec::new())
+            {
+                panic!("Unchecked dependency would create a cycle: {:?}", cycle);
+            }
+        }
 
         self.dependencies
             .entry(node_pos)
-            .and_modify(|entry| {
-                entry.insert(dep_pos);
-            })
-            .or_insert_with(|| {
-                let mut deps = HashSet::with_capacity(1);
-                deps.insert(dep_pos);
-                deps
-            });
+            .or_insert_with(|| DepSet::with_capacity(1))
+            .insert(dep_pos)
+    }
+
+    // If the graph is kept acyclic, check that making `node` depend upon each of
+    // `depends_on` would not create a cycle, and update the maintained order for those
+    // dependencies. If any would create a cycle, nothing is changed and the would-be
+    // cycle is returned. Nodes need not exist yet. Any ranks changed are recorded in
+    // `undo` (see `_rollback_order`).
+    fn _check_dependencies(
+        &mut self,
+        node: &T,
+        depends_on: &[T],
+        undo: &mut Vec<(usize, Vec<usize>)>,
+    ) -> Result<(), SolventError> {
+        if self.acyclic.is_none() {
+            return Ok(());
+        }
+        let node_pos = self._pos(node);
+        let mut edges: Vec<(usize, usize)> = Vec::with_capacity(depends_on.len());
+        for dep in depends_on.iter() {
+            if dep == node {
+                let pos = node_pos.unwrap_or(self.nodes.len());
+                return Err(SolventError::CycleDetected(format!("{:?}", [pos, pos])));
+            }
+            if let (Some(n), Some(d)) = (node_pos, self._pos(dep)) {
+                edges.push((n, d));
+            }
+        }
+        self._order_dependencies(&edges, undo)
+    }
+
+    // If the graph is kept acyclic, update the maintained order for the dependencies
+    // `edges` (by position), which must either all be from the same node or all be upon
+    // the same node. If any would create a cycle, nothing is changed and the would-be
+    // cycle is returned. Any ranks changed are recorded in `undo`.
+    fn _order_dependencies(
+        &mut self,
+        edges: &[(usize, usize)],
+        undo: &mut Vec<(usize, Vec<usize>)>,
+    ) -> Result<(), SolventError> {
+        if let Some(order) = self.acyclic.as_mut() {
+            // Any cycle created by one of these dependencies would pass through the node
+            // they share, so the others need not be in place to find it. If one would,
+            // undo the re-ranking done for those before it.
+            let start = undo.len();
+            for (n, d) in edges.iter() {
+                if let Err(cycle) = order.add_dependency(&self.dependencies, *n, *d, undo) {
+                    order.rollback(undo.split_off(start));
+                    return Err(SolventError::CycleDetected(format!("{:?}", cycle)));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Undo the re-ranking recorded by `_check_dependencies` or `_order_dependencies`
+    fn _rollback_order(&mut self, undo: Vec<(usize, Vec<usize>)>) {
+        if let Some(order) = self.acyclic.as_mut() {
+            order.rollback(undo);
+        }
+    }
+
+    /// Register a soft preference for `before` to come before `after`. Unlike a
+    /// dependency, a hint never brings either node into a resolution; it is only used to
+    /// choose between orders which are otherwise equally valid, when both nodes are being
+    /// resolved. Hints are followed by `resolve` and the methods built upon it, by
+    /// `dependencies_of`, `resolve_with_metrics` and `resolve_annotated`, and are ignored
+    /// where they conflict with dependencies. Neither node needs to be registered yet;
+    /// a hint takes effect once both are.
+    pub fn register_hint(&mut self, before: T, after: T) {
+        if before != after && !self.hints.iter().any(|(b, a)| *b == before && *a == after) {
+            self.hints.push((before, after));
+        }
+    }
+
+    // Positions of the nodes of each hint of which both nodes are registered
+    fn _hint_positions(&self) -> Vec<(usize, usize)> {
+        self.hints
+            .iter()
+            .filter_map(|(before, after)| Some((self._pos(before)?, self._pos(after)?)))
+            .filter(|(before, after)| before != after)
+            .collect()
+    }
+
+    // Positions of the nodes of each hint of which both nodes are amongst `plan`. Only
+    // the nodes of `plan` are searched, rather than the whole graph.
+    fn _hints_within(&self, plan: &[usize]) -> Vec<(usize, usize)> {
+        let find = |node: &T| {
+            plan.iter()
+                .cloned()
+                .find(|n| self.nodes[*n] == *node)
+                .or_else(|| {
+                    self.aliases
+                        .iter()
+                        .find(|(alias, _)| alias == node)
+                        .map(|(_, pos)| *pos)
+                        .filter(|pos| plan.contains(pos))
+                })
+        };
+        self.hints
+            .iter()
+            .filter_map(|(before, after)| Some((find(before)?, find(after)?)))
+            .filter(|(before, after)| before != after)
+            .collect()
+    }
+
+    // Reorder `order`, a resolution order, to follow any hints which apply to it
+    fn _apply_hints(&self, order: Vec<usize>) -> Vec<usize> {
+        if self.hints.is_empty() {
+            return order;
+        }
+        let hints = self._hints_within(&order);
+        if hints.is_empty() {
+            return order;
+        }
+        apply_hints(order, &hints, &|n| self._deps_of(n).collect())
+    }
+
+    /// Register a node (if it is not already registered) and get a handle to it, so that
+    /// dependencies can be chained fluently:
+    ///
+    /// ```
+    /// # use solvent::DepGraph;
+    /// let mut depgraph: DepGraph<&str> = DepGraph::new();
+    /// depgraph.node("a").depends_on("b").depends_on("c");
+    /// ```
+    pub fn node(&mut self, value: T) -> NodeHandle<'_, T> {
+        let pos = self._declare_node(value);
+        NodeHandle {
+            depgraph: self,
+            pos,
+        }
     }
 
     /// Add multiple dependencies of one node to a DepGraph. The node does not need to
     /// pre-exist, nor does the dependency node. If the node does pre-exist, the
     /// depends_on will be added to its existing dependency list. Otherwise it will
     /// be created.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the graph was created with `new_acyclic` and any of the dependencies would
+    /// create a cycle, in which case none are registered. Use `try_register_dependencies`
+    /// to handle this as an error.
     pub fn register_dependencies(&mut self, node: T, mut depends_on: Vec<T>) {
-        let node_pos = self._register_node(node);
+        if let Err(e) = self._check_dependencies(&node, &depends_on, &mut Vec::new()) {
+            panic!("Dependency would create a cycle: {}", e);
+        }
+        let node_pos = self._declare_node(node);
 
         let dep_poses = depends_on
             .drain(..)
             .map(|dp| self._register_node(dp))
             .collect::<Vec<_>>();
 
-        self.dependencies
-            .entry(node_pos)
-            .and_modify(|entry| {
-                entry.extend(dep_poses.iter());
-            })
-            .or_insert_with(|| dep_poses.iter().cloned().collect::<HashSet<_>>());
+        self.dependencies.entry(node_pos).or_default();
+        for dep_pos in dep_poses.into_iter() {
+            self._register_dependency(node_pos, dep_pos);
+        }
     }
 
-    /// This marks a node as satisfied. Iterators will not output such nodes. Nodes
-    /// must exist.
-    pub fn mark_as_satisfied(&mut self, nodes: &[T]) -> Result<(), SolventError> {
-        for node in nodes.iter() {
-            let node_pos = self._pos(node).ok_or(SolventError::NoSuchNode)?;
-            self.satisfied.insert(node_pos);
+    /// Add multiple dependencies of one node to a DepGraph, as `register_dependencies`
+    /// does, unless there are more than `max` of them, in which case
+    /// `SolventError::TooManyDependencies` is returned and nothing is registered. This
+    /// guards against unreasonably large input from untrusted sources. Dependencies which
+    /// would create a cycle are refused as by `try_register_dependencies`.
+    pub fn register_dependencies_bounded(
+        &mut self,
+        node: T,
+        depends_on: Vec<T>,
+        max: usize,
+    ) -> Result<(), SolventError> {
+        if depends_on.len() > max {
+            return Err(SolventError::TooManyDependencies(depends_on.len(), max));
         }
-
-        Ok(())
+        self.try_register_dependencies(node, depends_on)
     }
 
-    /// Get an iterator to iterate through the dependencies of the target node. Target
-    /// node must exist.
-    pub fn dependencies_of<'a>(
-        &'a self,
-        target: &T,
-    ) -> Result<DepGraphIterator<'a, T>, SolventError> {
-        let pos = self._pos(target).ok_or(SolventError::NoSuchNode)?;
+    /// Add multiple dependencies of one node to a DepGraph, as `register_dependencies`
+    /// does, but taking an array so that literals can be passed directly:
+    ///
+    /// ```
+    /// # use solvent::DepGraph;
+    /// let mut depgraph: DepGraph<&str> = DepGraph::new();
+    /// depgraph.register_dependencies_arr("a", ["b", "c", "d"]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics as `register_dependencies` does if a dependency would create a cycle.
+    pub fn register_dependencies_arr<const N: usize>(&mut self, node: T, depends_on: [T; N]) {
+        if let Err(e) = self._check_dependencies(&node, &depends_on, &mut Vec::new()) {
+            panic!("Dependency would create a cycle: {}", e);
+        }
+        let node_pos = self._declare_node(node);
 
-        Ok(DepGraphIterator {
-            depgraph: self,
-            target: pos,
-            satisfied: self.satisfied.clone(),
-            curpath: Vec::new(),
-            halted: false,
-        })
+        let dep_poses = IntoIterator::into_iter(depends_on)
+            .map(|dp| self._register_node(dp))
+            .collect::<Vec<_>>();
+
+        self.dependencies.entry(node_pos).or_default();
+        for dep_pos in dep_poses.into_iter() {
+            self._register_dependency(node_pos, dep_pos);
+        }
     }
-}
 
-/// This iterates through the dependencies of the DepGraph's target
-pub struct DepGraphIterator<'a, T: Eq + 'a> {
-    depgraph: &'a DepGraph<T>,
+    /// Add multiple dependencies of one node to a DepGraph, as `register_dependencies`
+    /// does, and return the number of duplicate dependencies which were collapsed, whether
+    /// duplicated within `depends_on` or already registered.
+    ///
+    /// # Panics
+    ///
+    /// Panics as `register_dependencies` does if a dependency would create a cycle.
+    pub fn register_dependencies_reporting(&mut self, node: T, mut depends_on: Vec<T>) -> usize {
+        if let Err(e) = self._check_dependencies(&node, &depends_on, &mut Vec::new()) {
+            panic!("Dependency would create a cycle: {}", e);
+        }
+        let node_pos = self._declare_node(node);
 
-    // Target we are trying to satisfy
-    target: usize,
+        let dep_poses = depends_on
+            .drain(..)
+            .map(|dp| self._register_node(dp))
+            .collect::<Vec<_>>();
 
-    // Node positions already satisfied during this iterator's walk
-    satisfied: HashSet<usize>,
+        self.dependencies.entry(node_pos).or_default();
+        dep_poses
+            .into_iter()
+            .filter(|dep_pos| !self._register_dependency(node_pos, *dep_pos))
+            .count()
+    }
 
-    // Current path, for cycle detection
-    curpath: Vec<usize>,
+    /// Add a dependency to a DepGraph, as `register_dependency` does, unless doing so
+    /// would create a cycle, in which case `SolventError::CycleDetected` is returned with
+    /// the would-be cycle and the graph is left unchanged.
+    pub fn try_register_dependency(&mut self, node: T, depends_on: T) -> Result<(), SolventError> {
+        self.try_register_dependencies(node, vec![depends_on])
+    }
 
-    // Halted.  Used so that it can return None after an Err is returned.
-    halted: bool,
-}
+    /// Add multiple dependencies of one node to a DepGraph, as `register_dependencies`
+    /// does, unless any of them would create a cycle, in which case
+    /// `SolventError::CycleDetected` is returned with the first would-be cycle found and
+    /// none are registered.
+    pub fn try_register_dependencies(
+        &mut self,
+        node: T,
+        depends_on: Vec<T>,
+    ) -> Result<(), SolventError> {
+        if self.acyclic.is_some() {
+            self._check_dependencies(&node, &depends_on, &mut Vec::new())?;
+        } else {
+            let node_pos = self._pos(&node);
+            for dep in depends_on.iter() {
+                // A node depending upon itself is a cycle, whether or not it exists yet
+                if *dep == node {
+                    let pos = node_pos.unwrap_or(self.nodes.len());
+                    return Err(SolventError::CycleDetected(format!("{:?}", [pos, pos])));
+                }
+                if let (Some(node_pos), Some(dep_pos)) = (node_pos, self._pos(dep)) {
+                    if let Some(path) = self._path(dep_pos, node_pos) {
+                        let mut cycle = vec![node_pos];
+                        cycle.extend(path);
+                        return Err(SolventError::CycleDetected(format!("{:?}", cycle)));
+                    }
+                }
+            }
+        }
+        self.register_dependencies(node, depends_on);
+        Ok(())
+    }
 
-impl<'a, T: Eq> DepGraphIterator<'a, T> {
-    fn get_next_dependency(&mut self, pos: usize) -> Result<usize, SolventError> {
-        if self.curpath.contains(&pos) {
-            let s = format!("{:?}", self.curpath);
-            return Err(SolventError::CycleDetected(s));
+    /// Remove all of the dependencies of a node, keeping the node itself and any
+    /// dependencies other nodes have upon it. Node must exist.
+    pub fn clear_dependencies_of(&mut self, node: &T) -> Result<(), SolventError> {
+        let node_pos = self._pos(node).ok_or(SolventError::NoSuchNode)?;
+        if let Some(deplist) = self.dependencies.get_mut(&node_pos) {
+            deplist.clear();
         }
-        self.curpath.push(pos);
+        Ok(())
+    }
 
-        let deplist = match self.depgraph.dependencies.get(&pos) {
-            None => return Ok(pos),
-            Some(deplist) => deplist,
-        };
+    /// Rewrite every dependency upon `old` into a dependency upon `new`, registering `new`
+    /// if it does not yet exist. `old` itself is kept. If `new` itself depends upon `old`,
+    /// that dependency is left as it is rather than becoming a dependency of `new` upon
+    /// itself. Returns the number of dependencies rewritten. `old` must exist.
+    ///
+    /// If the graph was created with `new_acyclic` and the rewritten dependencies would
+    /// create a cycle, `SolventError::CycleDetected` is returned and nothing is changed.
+    pub fn replace_dependency_target(&mut self, old: &T, new: T) -> Result<usize, SolventError> {
+        let old_pos = self._pos(old).ok_or(SolventError::NoSuchNode)?;
+        let mut dependents: Vec<usize> = self
+            .dependencies
+            .iter()
+            .filter(|(_, deplist)| deplist.contains(&old_pos))
+            .map(|(n, _)| *n)
+            .collect();
+        dependents.sort_unstable();
 
-        for n in deplist.iter() {
-            // Prune satisfied nodes
-            if self.satisfied.contains(n) {
-                continue;
+        if let Some(new_pos) = self._pos(&new) {
+            if new_pos == old_pos {
+                return Ok(dependents.len());
+            }
+            dependents.retain(|n| *n != new_pos);
+            if self.acyclic.is_some() {
+                // Nothing which depends upon `old` may be reachable from `new`
+                for n in dependents.iter() {
+                    if let Some(path) = self._path(new_pos, *n) {
+                        let mut cycle = vec![*n];
+                        cycle.extend(path);
+                        return Err(SolventError::CycleDetected(format!("{:?}", cycle)));
+                    }
+                }
             }
-            return self.get_next_dependency(*n);
         }
 
-        // nodes dependencies are satisfied
-        Ok(pos)
+        let new_pos = self._register_node(new);
+        for n in dependents.iter() {
+            if let Some(deplist) = self.dependencies.get_mut(n) {
+                deplist.remove(&old_pos);
+            }
+            self._register_dependency(*n, new_pos);
+        }
+        Ok(dependents.len())
     }
-}
 
-impl<'a, T: Eq> Iterator for DepGraphIterator<'a, T> {
-    type Item = Result<&'a T, SolventError>;
+    /// Remove the dependency entries of nodes which have no dependencies left (e.g. after
+    /// `clear_dependencies_of`), releasing their memory. This does not change how the
+    /// graph resolves.
+    pub fn prune_empty_dependency_sets(&mut self) {
+        self.dependencies.retain(|_, deplist| !deplist.is_empty());
+    }
 
-    // Get next dependency.  Returns None when finished.  If Some(Err(SolventError)) occurs,
-    // all subsequent calls will return None.
-    fn next(&mut self) -> Option<Result<&'a T, SolventError>> {
-        if self.halted {
-            return None;
+    /// Keep only the dependencies `node -> depends_on` for which `f(node, depends_on)`
+    /// returns true, removing the rest. No nodes are removed.
+    pub fn retain_edges<F: FnMut(&T, &T) -> bool>(&mut self, mut f: F) {
+        let nodes = &self.nodes;
+        for (n, deplist) in self.dependencies.iter_mut() {
+            let removed: Vec<usize> = deplist
+                .iter()
+                .cloned()
+                .filter(|dep| !f(&nodes[*n], &nodes[*dep]))
+                .collect();
+            for dep in removed.iter() {
+                deplist.remove(dep);
+            }
         }
+    }
 
-        let npos = self.target;
-        if self.satisfied.contains(&npos) {
-            self.halted = true;
-            return None;
+    /// Check whether `node` directly depends upon `depends_on`. Transitive dependencies
+    /// do not count. Returns false if either node does not exist.
+    pub fn has_dependency(&self, node: &T, depends_on: &T) -> bool {
+        match (self._pos(node), self._pos(depends_on)) {
+            (Some(node_pos), Some(dep_pos)) => self
+                .dependencies
+                .get(&node_pos)
+                .map_or(false, |deplist| deplist.contains(&dep_pos)),
+            _ => false,
         }
-
-        self.curpath.drain(..);
-        let next = match self.get_next_dependency(npos) {
-            Ok(d) => d,
-            Err(e) => {
-                self.halted = true;
-                return Some(Err(e));
-            }
-        };
-        self.satisfied.insert(next);
-        Some(Ok(&self.depgraph.nodes[next]))
     }
-}
-
-#[cfg(test)]
-mod test {
-    use super::DepGraph;
-    use super::HashSet;
-    use super::SolventError;
-
-    #[test]
-    fn solvent_test_branching() {
-        let mut depgraph: DepGraph<&str> = DepGraph::new();
-
-        depgraph.register_nodes(vec![
-            "a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l", "m", "n",
-        ]);
-
-        depgraph.register_dependencies("a", vec!["b", "c", "d"]);
-        depgraph.register_dependency("b", "d");
-        depgraph.register_dependencies("c", vec!["e", "m", "g"]);
-        depgraph.register_dependency("e", "f");
-        depgraph.register_dependency("g", "h");
-        depgraph.register_dependency("h", "i");
-        depgraph.register_dependencies("i", vec!["j", "k"]);
-        depgraph.register_dependencies("k", vec!["l", "m"]);
-        depgraph.register_dependency("m", "n");
 
-        let mut results = Vec::new();
+    /// Check whether registering the dependency `node -> depends_on` would create a cycle,
+    /// i.e. whether `node` is already reachable from `depends_on` (or they are the same
+    /// node). The graph is not modified. Nodes which do not yet exist cannot be part of a
+    /// cycle.
+    pub fn would_create_cycle(&self, node: &T, depends_on: &T) -> bool {
+        match (self._pos(node), self._pos(depends_on)) {
+            (Some(node_pos), Some(dep_pos)) => self._path(dep_pos, node_pos).is_some(),
+            _ => false,
+        }
+    }
 
-        for node in depgraph.dependencies_of(&"a").unwrap() {
-            // detect infinite looping bugs
-            assert!(results.len() < 30);
+    /// Check whether neither of two nodes depends upon the other, directly or
+    /// transitively, so that they could be processed concurrently. A node is not
+    /// independent of itself. Cycles are tolerated. Both nodes must exist.
+    pub fn are_independent(&self, a: &T, b: &T) -> Result<bool, SolventError> {
+        let a_pos = self._pos(a).ok_or(SolventError::NoSuchNode)?;
+        let b_pos = self._pos(b).ok_or(SolventError::NoSuchNode)?;
+        Ok(self._path(a_pos, b_pos).is_none() && self._path(b_pos, a_pos).is_none())
+    }
 
-            let n = match node {
-                Err(e) => panic!("Solvent error detected: {:?}", e),
-                Ok(n) => n,
-            };
+    /// Get the number of dependencies along the shortest dependency path from `from` to
+    /// `to`, or `None` if `to` cannot be reached from `from`. A node is at distance 0 from
+    /// itself. Both nodes must exist.
+    pub fn distance(&self, from: &T, to: &T) -> Result<Option<usize>, SolventError> {
+        let from_pos = self._pos(from).ok_or(SolventError::NoSuchNode)?;
+        let to_pos = self._pos(to).ok_or(SolventError::NoSuchNode)?;
 
-            // Check that all of that nodes dependencies have already been output
-            let pos = depgraph._pos(&n).unwrap();
-            let deps: Option<&HashSet<usize>> = depgraph.dependencies.get(&pos);
-            if let Some(deps) = deps {
-                for dep in deps.iter() {
-                    assert!(results.contains(&depgraph.nodes[*dep]));
+        let mut visited: HashSet<usize> = HashSet::new();
+        visited.insert(from_pos);
+        let mut queue = VecDeque::new();
+        queue.push_back((from_pos, 0));
+        while let Some((n, hops)) = queue.pop_front() {
+            if n == to_pos {
+                return Ok(Some(hops));
+            }
+            for dep in self._deps_of(n) {
+                if visited.insert(dep) {
+                    queue.push_back((dep, hops + 1));
                 }
             }
-
-            results.push(*n);
         }
+        Ok(None)
+    }
 
-        // Be sure we actually output enough stuff
-        assert!(results.len() == 14);
+    /// Check whether `to` can be reached from `from` by following at most `max_len`
+    /// dependencies. The search stops at that depth, so this is cheaper than `distance` for
+    /// nearby nodes in a large graph. A node is reachable from itself in 0 steps. Both
+    /// nodes must exist.
+    pub fn contains_path(&self, from: &T, to: &T, max_len: usize) -> Result<bool, SolventError> {
+        let from_pos = self._pos(from).ok_or(SolventError::NoSuchNode)?;
+        let to_pos = self._pos(to).ok_or(SolventError::NoSuchNode)?;
 
-        // Be sure each output is unique
-        for result in results.iter() {
-            let mut count: usize = 0;
-            for result2 in results.iter() {
-                if result == result2 {
-                    count += 1;
-                }
+        let mut visited: HashSet<usize> = HashSet::new();
+        visited.insert(from_pos);
+        let mut frontier = vec![from_pos];
+        for _ in 0..max_len {
+            if frontier.is_empty() || frontier.contains(&to_pos) {
+                break;
             }
-            assert!(count == 1);
+            frontier = frontier
+                .into_iter()
+                .flat_map(|n| self._deps_of(n))
+                .filter(|dep| visited.insert(*dep))
+                .collect();
         }
+        Ok(visited.contains(&to_pos))
     }
 
-    #[test]
-    fn solvent_test_updating_dependencies() {
-        let mut depgraph: DepGraph<&str> = DepGraph::new();
+    // A dependency path from `from` to `to` (inclusive of both), if one exists
+    fn _path(&self, from: usize, to: usize) -> Option<Vec<usize>> {
+        let mut visited: HashSet<usize> = HashSet::new();
+        let mut path = vec![from];
+        let mut stack = vec![self._deps_of(from)];
+        visited.insert(from);
+        if from == to {
+            return Some(path);
+        }
+        while let Some(deps) = stack.last_mut() {
+            match deps.next() {
+                Some(dep) if dep == to => {
+                    path.push(dep);
+                    return Some(path);
+                }
+                Some(dep) => {
+                    if visited.insert(dep) {
+                        path.push(dep);
+                        stack.push(self._deps_of(dep));
+                    }
+                }
+                None => {
+                    stack.pop();
+                    path.pop();
+                }
+            }
+        }
+        None
+    }
 
-        depgraph.register_dependencies("a", vec!["b", "c"]);
-        depgraph.register_dependency("a", "d");
-        assert!(depgraph.dependencies.get(&0).unwrap().contains(&1));
-        assert!(depgraph.dependencies.get(&0).unwrap().contains(&2));
-        assert!(depgraph.dependencies.get(&0).unwrap().contains(&3));
+    // The direct dependencies of `pos`
+    fn _deps_of(&self, pos: usize) -> alloc::vec::IntoIter<usize> {
+        self.dependencies
+            .get(&pos)
+            .map(|deplist| deplist.iter().cloned().collect::<Vec<_>>())
+            .unwrap_or_default()
+            .into_iter()
     }
 
-    #[test]
-    fn solvent_test_circular() {
-        let mut depgraph: DepGraph<&str> = DepGraph::new();
-        depgraph.register_dependency("a", "b");
-        depgraph.register_dependency("b", "c");
-        depgraph.register_dependency("c", "a");
+    // Positions of the phantom nodes which `pos` depends upon, directly or indirectly
+    // (including below satisfied nodes), in ascending order
+    fn _reachable_phantoms(&self, pos: usize) -> Vec<usize> {
+        let mut phantoms: Vec<usize> = self
+            ._dependencies_closure(pos)
+            .into_iter()
+            .filter(|n| !self.declared.contains(n))
+            .collect();
+        phantoms.sort_unstable();
+        phantoms
+    }
 
-        for node in depgraph.dependencies_of(&"a").unwrap() {
-            assert!(node.is_err());
+    /// This marks a node as satisfied. Iterators will not output such nodes. Nodes
+    /// must exist.
+    pub fn mark_as_satisfied(&mut self, nodes: &[T]) -> Result<(), SolventError> {
+        for node in nodes.iter() {
+            let node_pos = self._pos(node).ok_or(SolventError::NoSuchNode)?;
+            self.satisfied.insert(node_pos);
         }
+
+        Ok(())
+    }
+
+    /// Take a snapshot of which nodes are satisfied, which can later be restored with
+    /// `restore_satisfied`.
+    pub fn snapshot_satisfied(&self) -> SatisfiedSnapshot {
+        SatisfiedSnapshot {
+            satisfied: self.satisfied.clone(),
+        }
+    }
+
+    /// Restore which nodes are satisfied from a snapshot previously taken of this graph
+    /// with `snapshot_satisfied`. Nodes are identified by position, so if nodes have
+    /// since been removed (e.g. by `merge_nodes`) the snapshot may mark different nodes;
+    /// positions no longer in the graph are dropped.
+    pub fn restore_satisfied(&mut self, snap: SatisfiedSnapshot) {
+        let len = self.nodes.len();
+        self.satisfied = snap.satisfied.iter().filter(|pos| *pos < len).collect();
+    }
+
+    /// Mark as satisfied every node which is satisfied in `other` and is also present in
+    /// this graph. Satisfied nodes of `other` which are absent from this graph are ignored.
+    pub fn copy_satisfied_from(&mut self, other: &DepGraph<T>) {
+        for other_pos in other.satisfied.iter() {
+            if let Some(pos) = self._pos(&other.nodes[other_pos]) {
+                self.satisfied.insert(pos);
+            }
+        }
+    }
+
+    /// Register `alias` as another name for `node`, as when a package provides a virtual
+    /// name that others depend upon. Afterwards `alias` refers to `node` wherever a node
+    /// is given, so dependencies upon `alias` are dependencies upon `node`. If `alias` is
+    /// already in the graph it is merged into `node` (see `merge_nodes`). `node` must
+    /// exist, and `alias` must not already be an alias of a different node
+    /// (`SolventError::DuplicateNode`). Merging `alias` removes it, shifting down the
+    /// index of every later node, so previously obtained `NodeId`s, `resolve_indices` and
+    /// `edge_index_list` results may no longer refer to the same nodes.
+    pub fn register_provides(&mut self, node: &T, alias: T) -> Result<(), SolventError> {
+        let node_pos = self._pos(node).ok_or(SolventError::NoSuchNode)?;
+        if let Some((_, pos)) = self.aliases.iter().find(|(a, _)| *a == alias) {
+            if *pos != node_pos {
+                return Err(SolventError::DuplicateNode);
+            }
+            return Ok(());
+        }
+        if self._pos(&alias).is_some() {
+            self.merge_nodes(node, &alias)?;
+        }
+        let node_pos = self._pos(node).ok_or(SolventError::NoSuchNode)?;
+        self.aliases.push((alias, node_pos));
+        Ok(())
+    }
+
+    /// Merge the node `merge` into the node `keep`. Dependencies of `merge` become
+    /// dependencies of `keep`, nodes depending on `merge` will depend on `keep` instead,
+    /// and if `merge` was satisfied then `keep` becomes satisfied. `merge` is then removed
+    /// from the graph. Both nodes must exist.
+    ///
+    /// Removing `merge` shifts down the index of every node registered after it, so any
+    /// `NodeId`s, `resolve_indices` or `edge_index_list` results obtained beforehand may no
+    /// longer refer to the same nodes.
+    ///
+    /// Merging takes time proportional to the size of the whole graph (O(V+E)). This
+    /// includes graphs created with `new_acyclic`, whose topological order is rebuilt.
+    pub fn merge_nodes(&mut self, keep: &T, merge: &T) -> Result<(), SolventError> {
+        let keep_pos = self._pos(keep).ok_or(SolventError::NoSuchNode)?;
+        let merge_pos = self._pos(merge).ok_or(SolventError::NoSuchNode)?;
+        if keep_pos == merge_pos {
+            return Ok(());
+        }
+        if self.acyclic.is_some() {
+            // Merging creates a cycle if either node can reach the other other than by
+            // a direct dependency
+            let keep_deps = self._deps_of(keep_pos).map(|d| (d, merge_pos));
+            let merge_deps = self._deps_of(merge_pos).map(|d| (d, keep_pos));
+            for (from, to) in keep_deps.chain(merge_deps) {
+                if from != to {
+                    if let Some(path) = self._path(from, to) {
+                        return Err(SolventError::CycleDetected(format!("{:?}", path)));
+                    }
+                }
+            }
+        }
+
+        if let Some(deplist) = self.dependencies.remove(&merge_pos) {
+            self.dependencies
+                .entry(keep_pos)
+                .or_default()
+                .extend(deplist.into_iter().filter(|d| *d != keep_pos));
+        }
+        for (node_pos, deplist) in self.dependencies.iter_mut() {
+            if deplist.remove(&merge_pos) && *node_pos != keep_pos {
+                deplist.insert(keep_pos);
+            }
+        }
+        if self.satisfied.contains(&merge_pos) {
+            self.satisfied.insert(keep_pos);
+        }
+        if let Some(tags) = self.tags.remove(&merge_pos) {
+            self.tags.entry(keep_pos).or_default().extend(tags);
+        }
+        if self.declared.contains(&merge_pos) {
+            self.declared.insert(keep_pos);
+        }
+        for (_, pos) in self.aliases.iter_mut() {
+            if *pos == merge_pos {
+                *pos = keep_pos;
+            }
+        }
+        self._remove_node(merge_pos);
+        Ok(())
+    }
+
+    // Remove the node at `pos` along with any edges to or from it, shifting down the
+    // positions of all subsequent nodes.
+    fn _remove_node(&mut self, pos: usize) -> T {
+        let shift = |n: usize| if n > pos { n - 1 } else { n };
+
+        let dependencies = core::mem::take(&mut self.dependencies);
+        self.dependencies = dependencies
+            .into_iter()
+            .filter(|(n, _)| *n != pos)
+            .map(|(n, deplist)| {
+                let deplist = deplist
+                    .into_iter()
+                    .filter(|d| *d != pos)
+                    .map(shift)
+                    .collect();
+                (shift(n), deplist)
+            })
+            .collect();
+
+        let satisfied = core::mem::take(&mut self.satisfied);
+        self.satisfied = satisfied.iter().filter(|n| *n != pos).map(shift).collect();
+
+        let tags = core::mem::take(&mut self.tags);
+        self.tags = tags
+            .into_iter()
+            .filter(|(n, _)| *n != pos)
+            .map(|(n, t)| (shift(n), t))
+            .collect();
+
+        let declared = core::mem::take(&mut self.declared);
+        self.declared = declared
+            .into_iter()
+            .filter(|n| *n != pos)
+            .map(shift)
+            .collect();
+
+        self.aliases.retain(|(_, n)| *n != pos);
+        for (_, n) in self.aliases.iter_mut() {
+            *n = shift(*n);
+        }
+
+        // Edges redirected by `merge_nodes` may not respect the maintained order, so it
+        // is rebuilt; this is no worse than shifting every position above.
+        let node = self.nodes.remove(pos);
+        if self.acyclic.is_some() {
+            let order = self
+                ._structural_order()
+                .expect("an acyclic graph has a topological order");
+            self.acyclic = Some(TopoOrder::from_order(order));
+        }
+        node
+    }
+
+    /// Check that the graph's internal state is consistent: that every node position
+    /// recorded in its dependencies, satisfied set, tags and aliases refers to a
+    /// node in the graph. A graph built through this API always passes; this guards
+    /// against bugs. Returns `SolventError::CorruptGraph` describing the first problem.
+    pub fn validate_integrity(&self) -> Result<(), SolventError> {
+        let len = self.nodes.len();
+        let check = |pos: usize, what: &str| {
+            if pos < len {
+                Ok(())
+            } else {
+                Err(SolventError::CorruptGraph(format!(
+                    "{} index {} out of range ({} nodes)",
+                    what, pos, len
+                )))
+            }
+        };
+
+        let mut edges: Vec<(&usize, &DepSet)> = self.dependencies.iter().collect();
+        edges.sort_unstable_by_key(|(n, _)| **n);
+        for (n, deplist) in edges.into_iter() {
+            check(*n, "dependent node")?;
+            let mut deps: Vec<usize> = deplist.iter().cloned().collect();
+            deps.sort_unstable();
+            for dep in deps.into_iter() {
+                check(dep, "dependency")?;
+            }
+        }
+        for n in self.satisfied.iter() {
+            check(n, "satisfied node")?;
+        }
+        let mut declared: Vec<usize> = self.declared.iter().cloned().collect();
+        declared.sort_unstable();
+        for n in declared.into_iter() {
+            check(n, "declared node")?;
+        }
+        let mut tagged: Vec<usize> = self.tags.keys().cloned().collect();
+        tagged.sort_unstable();
+        for n in tagged.into_iter() {
+            check(n, "tagged node")?;
+        }
+        for (_, n) in self.aliases.iter() {
+            check(*n, "aliased node")?;
+        }
+        Ok(())
+    }
+
+    /// Check that every satisfied node has all of its dependencies satisfied too. If not,
+    /// the `(satisfied_node, unsatisfied_dependency)` pairs which violate this are
+    /// returned.
+    pub fn check_satisfied_consistency(&self) -> Result<(), Vec<(&T, &T)>> {
+        let violations = self._satisfied_violations();
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations
+                .into_iter()
+                .map(|(n, d)| (&self.nodes[n], &self.nodes[d]))
+                .collect())
+        }
+    }
+
+    // Positions of satisfied nodes paired with each of their unsatisfied dependencies
+    fn _satisfied_violations(&self) -> Vec<(usize, usize)> {
+        let mut violations = Vec::new();
+        for n in (0..self.nodes.len()).filter(|n| self.satisfied.contains(n)) {
+            for dep in self.dependencies.get(&n).into_iter().flatten() {
+                if !self.satisfied.contains(dep) {
+                    violations.push((n, *dep));
+                }
+            }
+        }
+        violations
+    }
+
+    /// Attach a tag to a node. Node must exist.
+    pub fn tag_node(&mut self, node: &T, tag: String) -> Result<(), SolventError> {
+        let node_pos = self._pos(node).ok_or(SolventError::NoSuchNode)?;
+        self.tags.entry(node_pos).or_default().insert(tag);
+        Ok(())
+    }
+
+    /// Resolve the dependencies of the target node, as `dependencies_of` does, except that
+    /// nodes carrying any of the `excluded` tags are treated as satisfied: they are not
+    /// output and their dependencies are not descended into. Target node must exist.
+    pub fn resolve_excluding_tags<'a>(
+        &'a self,
+        target: &T,
+        excluded: &[&str],
+    ) -> Result<Vec<&'a T>, SolventError> {
+        let pos = self._pos(target).ok_or(SolventError::NoSuchNode)?;
+        let skip = |n| {
+            self.satisfied.contains(&n)
+                || self.tags.get(&n).map_or(false, |tags| {
+                    tags.iter().any(|t| excluded.contains(&t.as_str()))
+                })
+        };
+
+        let mut visited = HashSet::new();
+        let mut order = Vec::new();
+        self._visit(pos, &skip, &mut visited, &mut order)?;
+        Ok(order.into_iter().map(|n| &self.nodes[n]).collect())
+    }
+
+    /// Get an iterator to iterate through the dependencies of the target node. If any hint
+    /// (see `register_hint`) names two of the nodes the target depends upon, the whole
+    /// order is resolved by the first call to `next`; should that hit an error, the nodes
+    /// resolved before it are still output ahead of the error. Target node must exist.
+    pub fn dependencies_of<'a>(
+        &'a self,
+        target: &T,
+    ) -> Result<DepGraphIterator<'a, T>, SolventError> {
+        let pos = self._pos(target).ok_or(SolventError::NoSuchNode)?;
+
+        Ok(DepGraphIterator {
+            depgraph: self,
+            target: pos,
+            satisfied: self.satisfied.clone(),
+            curpath: Vec::new(),
+            halted: false,
+            seed: None,
+            hints_checked: false,
+            hinted: None,
+            error: None,
+        })
+    }
+
+    /// Get an iterator to iterate through the dependencies of the target node, as
+    /// `dependencies_of` does, but with the dependencies of each node visited in an order
+    /// shuffled according to `seed`. The same seed always gives the same order, while
+    /// different seeds explore different valid orders. Target node must exist.
+    pub fn dependencies_of_seeded<'a>(
+        &'a self,
+        target: &T,
+        seed: u64,
+    ) -> Result<DepGraphIterator<'a, T>, SolventError> {
+        let mut iter = self.dependencies_of(target)?;
+        iter.seed = Some(seed);
+        Ok(iter)
+    }
+
+    /// Resolve the dependencies of the target node as `resolve` does, but at each node
+    /// visit its dependencies in the order given by `cmp`, rather than in whatever order
+    /// they happen to be stored. Target node must exist.
+    pub fn dependencies_of_by<'a, F: Fn(&T, &T) -> Ordering>(
+        &'a self,
+        target: &T,
+        cmp: F,
+    ) -> Result<Vec<&'a T>, SolventError> {
+        let pos = self._pos(target).ok_or(SolventError::NoSuchNode)?;
+        let mut visited = HashSet::new();
+        let mut curpath = Vec::new();
+        let mut order = Vec::new();
+        self._visit_by(pos, &cmp, &mut visited, &mut curpath, &mut order)?;
+        Ok(order.into_iter().map(|n| &self.nodes[n]).collect())
+    }
+
+    fn _visit_by(
+        &self,
+        pos: usize,
+        cmp: &dyn Fn(&T, &T) -> Ordering,
+        visited: &mut HashSet<usize>,
+        curpath: &mut Vec<usize>,
+        order: &mut Vec<usize>,
+    ) -> Result<(), SolventError> {
+        if curpath.contains(&pos) {
+            let s = format!("{:?}", curpath);
+            return Err(SolventError::CycleDetected(s));
+        }
+        if visited.contains(&pos) || self.satisfied.contains(&pos) {
+            return Ok(());
+        }
+        curpath.push(pos);
+        let mut deplist: Vec<usize> = self._deps_of(pos).collect();
+        deplist.sort_by(|a, b| cmp(&self.nodes[*a], &self.nodes[*b]));
+        for dep in deplist.into_iter() {
+            self._visit_by(dep, cmp, visited, curpath, order)?;
+        }
+        curpath.pop();
+        visited.insert(pos);
+        order.push(pos);
+        Ok(())
+    }
+
+    /// Resolve the dependencies of the target node as `resolve` does, calling `on_enter`
+    /// as the walk enters each node and `on_leave` once all of that node's dependencies
+    /// have been walked, so that the calls for a node bracket those for its dependencies.
+    /// Satisfied nodes and nodes already walked are not entered. Target node must exist.
+    pub fn resolve_traced<FEnter: FnMut(&T), FLeave: FnMut(&T)>(
+        &self,
+        target: &T,
+        mut on_enter: FEnter,
+        mut on_leave: FLeave,
+    ) -> Result<Vec<&T>, SolventError> {
+        let pos = self._pos(target).ok_or(SolventError::NoSuchNode)?;
+        let mut visited = HashSet::new();
+        let mut curpath = Vec::new();
+        let mut order = Vec::new();
+        self._visit_traced(
+            pos,
+            &mut on_enter,
+            &mut on_leave,
+            &mut visited,
+            &mut curpath,
+            &mut order,
+        )?;
+        Ok(order.into_iter().map(|n| &self.nodes[n]).collect())
+    }
+
+    fn _visit_traced(
+        &self,
+        pos: usize,
+        on_enter: &mut dyn FnMut(&T),
+        on_leave: &mut dyn FnMut(&T),
+        visited: &mut HashSet<usize>,
+        curpath: &mut Vec<usize>,
+        order: &mut Vec<usize>,
+    ) -> Result<(), SolventError> {
+        if curpath.contains(&pos) {
+            let s = format!("{:?}", curpath);
+            return Err(SolventError::CycleDetected(s));
+        }
+        if visited.contains(&pos) || self.satisfied.contains(&pos) {
+            return Ok(());
+        }
+        on_enter(&self.nodes[pos]);
+        curpath.push(pos);
+        for dep in self._deps_of(pos) {
+            self._visit_traced(dep, on_enter, on_leave, visited, curpath, order)?;
+        }
+        curpath.pop();
+        on_leave(&self.nodes[pos]);
+        visited.insert(pos);
+        order.push(pos);
+        Ok(())
+    }
+
+    /// Get the phantom nodes: those which have only ever been referenced as a dependency
+    /// of another node, and never registered in their own right (with `register_node` or
+    /// as the dependent node when registering dependencies).
+    pub fn phantom_nodes(&self) -> Vec<&T> {
+        (0..self.nodes.len())
+            .filter(|n| !self.declared.contains(n))
+            .map(|n| &self.nodes[n])
+            .collect()
+    }
+
+    /// Resolve the dependencies of the target node, returning them in the order that
+    /// `dependencies_of` would output them. Target node must exist.
+    ///
+    /// If the target itself is satisfied, nothing needs to be done and the result is
+    /// empty. Use `resolve_status` to distinguish this case explicitly.
+    ///
+    /// Satisfied nodes are not descended into, so nodes reachable only through satisfied
+    /// nodes are never visited, however large that part of the graph is.
+    pub fn resolve<'a>(&'a self, target: &T) -> Result<Vec<&'a T>, SolventError> {
+        let pos = self._pos(target).ok_or(SolventError::NoSuchNode)?;
+        Ok(self
+            ._resolve(pos)?
+            .into_iter()
+            .map(|n| &self.nodes[n])
+            .collect())
+    }
+
+    /// Resolve the dependencies of the target node as `resolve` does, but report
+    /// explicitly whether the target is already satisfied. Target node must exist.
+    pub fn resolve_status<'a>(&'a self, target: &T) -> Result<ResolveStatus<'a, T>, SolventError> {
+        let pos = self._pos(target).ok_or(SolventError::NoSuchNode)?;
+        if self.satisfied.contains(&pos) {
+            return Ok(ResolveStatus::AlreadySatisfied);
+        }
+        Ok(ResolveStatus::Pending(
+            self._resolve(pos)?
+                .into_iter()
+                .map(|n| &self.nodes[n])
+                .collect(),
+        ))
+    }
+
+    /// Get every node the target node depends upon, directly or transitively, and the
+    /// target itself, in dependency order following any hints, each with whether `resolve`
+    /// would output it (`NodeStatus::Pending`) or skip it (`NodeStatus::Satisfied`)
+    /// because it is satisfied or only needed through a satisfied node. Unlike `resolve`,
+    /// satisfied nodes and their dependencies are included. Target node must exist.
+    pub fn resolve_annotated<'a>(
+        &'a self,
+        target: &T,
+    ) -> Result<Vec<(&'a T, NodeStatus)>, SolventError> {
+        let pos = self._pos(target).ok_or(SolventError::NoSuchNode)?;
+        let pending: HashSet<usize> = self._resolve(pos)?.into_iter().collect();
+        let mut visited = HashSet::new();
+        let mut order = Vec::new();
+        self._visit(pos, &|_| false, &mut visited, &mut order)?;
+        Ok(self
+            ._apply_hints(order)
+            .into_iter()
+            .map(|n| {
+                let status = if pending.contains(&n) {
+                    NodeStatus::Pending
+                } else {
+                    NodeStatus::Satisfied
+                };
+                (&self.nodes[n], status)
+            })
+            .collect())
+    }
+
+    /// Resolve the dependencies of the target node, as `resolve` does, but return an
+    /// empty Vec rather than an error if the target does not exist. Note that an empty
+    /// Vec is also returned if a cycle is detected; use `resolve` to tell these apart.
+    pub fn resolve_or_empty<'a>(&'a self, target: &T) -> Vec<&'a T> {
+        self.resolve(target).unwrap_or_default()
+    }
+
+    /// Resolve the dependencies of the target node as `resolve` does, but without the
+    /// target itself: only the nodes which must be processed before it. Target node must
+    /// exist.
+    pub fn dependencies_only<'a>(&'a self, target: &T) -> Result<Vec<&'a T>, SolventError> {
+        let pos = self._pos(target).ok_or(SolventError::NoSuchNode)?;
+        let mut order = self._resolve(pos)?;
+        if order.last() == Some(&pos) {
+            order.pop();
+        }
+        Ok(order.into_iter().map(|n| &self.nodes[n]).collect())
+    }
+
+    /// Resolve the dependencies of the target node as `resolve` does, but return only the
+    /// nodes for which `keep` returns true, in the same order. All of the dependencies are
+    /// still walked; only the output is filtered. Target node must exist.
+    pub fn resolve_filtered<'a, F: Fn(&T) -> bool>(
+        &'a self,
+        target: &T,
+        keep: F,
+    ) -> Result<Vec<&'a T>, SolventError> {
+        let pos = self._pos(target).ok_or(SolventError::NoSuchNode)?;
+        Ok(self
+            ._resolve(pos)?
+            .into_iter()
+            .map(|n| &self.nodes[n])
+            .filter(|node| keep(node))
+            .collect())
+    }
+
+    /// Get the target node and everything which depends upon it, directly or indirectly,
+    /// in an order such that each node follows all of its dependencies amongst them (so
+    /// the target comes first). This is the order in which to redo work after the target
+    /// has changed, so satisfied nodes are included. Target node must exist.
+    pub fn resolve_dependents<'a>(&'a self, target: &T) -> Result<Vec<&'a T>, SolventError> {
+        let pos = self._pos(target).ok_or(SolventError::NoSuchNode)?;
+        let dependents = self._dependents_closure(pos);
+
+        let skip = |n| !dependents.contains(&n);
+        let mut visited = HashSet::new();
+        let mut order = Vec::new();
+        for n in (0..self.nodes.len()).filter(|n| dependents.contains(n)) {
+            self._visit(n, &skip, &mut visited, &mut order)?;
+        }
+        Ok(order.into_iter().map(|n| &self.nodes[n]).collect())
+    }
+
+    /// Get the nodes which would need processing again if `node` were invalidated: the
+    /// node itself and every node which depends upon it, directly or indirectly. Satisfied
+    /// nodes are included. Cycles are tolerated. Nodes are listed in the order they were
+    /// registered, in a Vec rather than a HashSet as the HashSet type varies with the
+    /// `alloc` feature. Node must exist.
+    pub fn blast_radius(&self, node: &T) -> Result<Vec<&T>, SolventError> {
+        let pos = self._pos(node).ok_or(SolventError::NoSuchNode)?;
+        let mut radius: Vec<usize> = self._dependents_closure(pos).into_iter().collect();
+        radius.sort_unstable();
+        Ok(radius.into_iter().map(|n| &self.nodes[n]).collect())
+    }
+
+    // Positions of `pos` and of every node it depends upon, directly or indirectly
+    fn _dependencies_closure(&self, pos: usize) -> HashSet<usize> {
+        let mut closure = HashSet::new();
+        closure.insert(pos);
+        let mut stack = vec![pos];
+        while let Some(n) = stack.pop() {
+            stack.extend(self._deps_of(n).filter(|dep| closure.insert(*dep)));
+        }
+        closure
+    }
+
+    // Positions of `pos` and of every node which depends upon it, directly or indirectly
+    fn _dependents_closure(&self, pos: usize) -> HashSet<usize> {
+        let mut dependents: HashMap<usize, Vec<usize>> = HashMap::new();
+        for (n, deplist) in self.dependencies.iter() {
+            for dep in deplist.iter() {
+                dependents.entry(*dep).or_default().push(*n);
+            }
+        }
+
+        let mut closure = HashSet::new();
+        closure.insert(pos);
+        let mut queue = VecDeque::new();
+        queue.push_back(pos);
+        while let Some(n) = queue.pop_front() {
+            for dependent in dependents.get(&n).into_iter().flatten() {
+                if closure.insert(*dependent) {
+                    queue.push_back(*dependent);
+                }
+            }
+        }
+        closure
+    }
+
+    /// Find the critical path of the target node: the chain of dependencies, ending with
+    /// the target, with the greatest total cost according to `cost`. Returns that total
+    /// and the chain in dependency order, with the total saturating at `u64::MAX`.
+    /// Satisfied nodes are skipped, so the result is empty if the target is satisfied.
+    /// Target node must exist.
+    pub fn critical_path<'a, F: Fn(&T) -> u64>(
+        &'a self,
+        target: &T,
+        cost: F,
+    ) -> Result<(u64, Vec<&'a T>), SolventError> {
+        let pos = self._pos(target).ok_or(SolventError::NoSuchNode)?;
+
+        // The heaviest chain below each node, found from the leaves upwards
+        let mut heaviest: HashMap<usize, (u64, Option<usize>)> = HashMap::new();
+        for n in self._resolve(pos)?.into_iter() {
+            let (below, next) = self
+                .dependencies
+                .get(&n)
+                .into_iter()
+                .flatten()
+                .filter_map(|d| heaviest.get(d).map(|(c, _)| (*c, Some(*d))))
+                .max_by_key(|(c, _)| *c)
+                .unwrap_or((0, None));
+            heaviest.insert(n, (cost(&self.nodes[n]).saturating_add(below), next));
+        }
+
+        let total = heaviest.get(&pos).map_or(0, |(c, _)| *c);
+        let mut path = Vec::new();
+        let mut next = heaviest.get(&pos).map(|_| pos);
+        while let Some(n) = next {
+            path.push(&self.nodes[n]);
+            next = heaviest[&n].1;
+        }
+        path.reverse();
+        Ok((total, path))
+    }
+
+    /// Resolve the dependencies of the target node as `resolve` does, but return only the
+    /// longest prefix of that order whose total cost according to `cost` stays within
+    /// `budget`, stopping before the first node which would exceed it. Target node must
+    /// exist.
+    pub fn affordable_dependencies<'a, F: Fn(&T) -> u64>(
+        &'a self,
+        target: &T,
+        budget: u64,
+        cost: F,
+    ) -> Result<Vec<&'a T>, SolventError> {
+        let mut spent: u64 = 0;
+        Ok(self
+            .resolve(target)?
+            .into_iter()
+            .take_while(|n| match spent.checked_add(cost(n)) {
+                Some(total) if total <= budget => {
+                    spent = total;
+                    true
+                }
+                _ => false,
+            })
+            .collect())
+    }
+
+    /// Get the nodes which resolving `a` requires but resolving `b` does not, in the order
+    /// `resolve(a)` would output them. Both nodes must exist.
+    pub fn requirement_diff<'a>(&'a self, a: &T, b: &T) -> Result<Vec<&'a T>, SolventError> {
+        let a_pos = self._pos(a).ok_or(SolventError::NoSuchNode)?;
+        let b_pos = self._pos(b).ok_or(SolventError::NoSuchNode)?;
+        let b_required: HashSet<usize> = self._resolve(b_pos)?.into_iter().collect();
+        Ok(self
+            ._resolve(a_pos)?
+            .into_iter()
+            .filter(|n| !b_required.contains(n))
+            .map(|n| &self.nodes[n])
+            .collect())
+    }
+
+    /// Get the nodes which every one of `nodes` depends upon, directly or indirectly, in
+    /// the order they were registered. Satisfied nodes are included. Returns an empty Vec
+    /// if `nodes` is empty. Nodes must exist.
+    pub fn common_dependencies<'a>(&'a self, nodes: &[T]) -> Result<Vec<&'a T>, SolventError> {
+        let mut common: Option<HashSet<usize>> = None;
+        for node in nodes.iter() {
+            let pos = self._pos(node).ok_or(SolventError::NoSuchNode)?;
+            let mut deps: HashSet<usize> = HashSet::new();
+            for dep in self._deps_of(pos) {
+                if !deps.contains(&dep) {
+                    deps.extend(self._dependencies_closure(dep));
+                }
+            }
+            common = Some(match common {
+                None => deps,
+                Some(common) => common.into_iter().filter(|n| deps.contains(n)).collect(),
+            });
+        }
+
+        let mut common: Vec<usize> = common.into_iter().flatten().collect();
+        common.sort_unstable();
+        Ok(common.into_iter().map(|n| &self.nodes[n]).collect())
+    }
+
+    /// Get the nodes which are ready to be processed next: those dependencies of the
+    /// target node (or the target itself) which are not satisfied, but all of whose own
+    /// dependencies are satisfied. Target node must exist.
+    pub fn ready_nodes<'a>(&'a self, target: &T) -> Result<Vec<&'a T>, SolventError> {
+        let pos = self._pos(target).ok_or(SolventError::NoSuchNode)?;
+        Ok(self
+            ._resolve(pos)?
+            .into_iter()
+            .filter(|n| {
+                self.dependencies
+                    .get(n)
+                    .into_iter()
+                    .flatten()
+                    .all(|d| self.satisfied.contains(d))
+            })
+            .map(|n| &self.nodes[n])
+            .collect())
+    }
+
+    /// Get the unsatisfied nodes which directly depend upon `node` and whose other
+    /// dependencies are all satisfied, so that they become ready to process once `node`
+    /// is satisfied. Nodes are returned in the order they were registered. Node must
+    /// exist.
+    pub fn unblocked_by<'a>(&'a self, node: &T) -> Result<Vec<&'a T>, SolventError> {
+        let pos = self._pos(node).ok_or(SolventError::NoSuchNode)?;
+        let mut unblocked: Vec<usize> = self
+            .dependencies
+            .iter()
+            .filter(|(n, deplist)| deplist.contains(&pos) && !self.satisfied.contains(n))
+            .filter(|(_, deplist)| {
+                deplist
+                    .iter()
+                    .all(|d| *d == pos || self.satisfied.contains(d))
+            })
+            .map(|(n, _)| *n)
+            .collect();
+        unblocked.sort_unstable();
+        Ok(unblocked.into_iter().map(|n| &self.nodes[n]).collect())
+    }
+
+    /// Get the depth first postorder of the dependencies of the target node: each node
+    /// follows all of its dependencies, and the target is last. Unlike `resolve`, this
+    /// ignores which nodes are satisfied. Target node must exist.
+    pub fn postorder<'a>(&'a self, target: &T) -> Result<Vec<&'a T>, SolventError> {
+        let pos = self._pos(target).ok_or(SolventError::NoSuchNode)?;
+        Ok(self
+            ._postorder(pos)?
+            .into_iter()
+            .map(|n| &self.nodes[n])
+            .collect())
+    }
+
+    /// Get the nodes at positions `start` (inclusive) to `end` (exclusive) in the
+    /// resolution of the target node, as `resolve` would return it. The range is clamped
+    /// to the length of the resolution. Target node must exist.
+    pub fn nodes_in_rank_range<'a>(
+        &'a self,
+        target: &T,
+        start: usize,
+        end: usize,
+    ) -> Result<Vec<&'a T>, SolventError> {
+        let order = self.resolve(target)?;
+        let end = end.min(order.len());
+        let start = start.min(end);
+        Ok(order[start..end].to_vec())
+    }
+
+    /// Resolve the dependencies of the target node, as `resolve` does, pairing each node
+    /// with its direct unsatisfied dependencies (all of which precede it in the output).
+    /// Target node must exist.
+    pub fn resolve_with_deps<'a>(
+        &'a self,
+        target: &T,
+    ) -> Result<Vec<(&'a T, Vec<&'a T>)>, SolventError> {
+        let pos = self._pos(target).ok_or(SolventError::NoSuchNode)?;
+        Ok(self
+            ._resolve(pos)?
+            .into_iter()
+            .map(|n| {
+                let deps = self
+                    .dependencies
+                    .get(&n)
+                    .into_iter()
+                    .flatten()
+                    .filter(|d| !self.satisfied.contains(d))
+                    .map(|d| &self.nodes[*d])
+                    .collect();
+                (&self.nodes[n], deps)
+            })
+            .collect())
+    }
+
+    /// Resolve the dependencies of the target node, as `resolve` does, but returning the
+    /// index of each node rather than the node itself. Indices are assigned in the order
+    /// nodes are first registered, starting at 0 (see `node_at`). Target node must exist.
+    pub fn resolve_indices(&self, target: &T) -> Result<Vec<usize>, SolventError> {
+        let pos = self._pos(target).ok_or(SolventError::NoSuchNode)?;
+        self._resolve(pos)
+    }
+
+    /// Resolve the dependencies of the target node, as `resolve` does, pairing each node
+    /// with its step number: its position in the resolution order, starting at 0. Steps
+    /// are numbered after satisfied nodes are left out, so they are always contiguous.
+    /// Target node must exist.
+    pub fn resolve_enumerated<'a>(
+        &'a self,
+        target: &T,
+    ) -> Result<Vec<(usize, &'a T)>, SolventError> {
+        let pos = self._pos(target).ok_or(SolventError::NoSuchNode)?;
+        Ok(self
+            ._resolve(pos)?
+            .into_iter()
+            .map(|n| &self.nodes[n])
+            .enumerate()
+            .collect())
+    }
+
+    /// Get the node at the given index, if there is one.
+    pub fn node_at(&self, index: usize) -> Option<&T> {
+        self.nodes.get(index)
+    }
+
+    /// Replace the value of the node at index `id`, keeping all of its dependencies,
+    /// dependents and state. Returns the old value. Errors with `NoSuchNode` if there is
+    /// no such index, and with `DuplicateNode` if `value` is already present at a
+    /// different index.
+    pub fn set_node_value(&mut self, id: NodeId, value: T) -> Result<T, SolventError> {
+        if id >= self.nodes.len() {
+            return Err(SolventError::NoSuchNode);
+        }
+        match self._pos(&value) {
+            Some(pos) if pos != id => Err(SolventError::DuplicateNode),
+            _ => Ok(core::mem::replace(&mut self.nodes[id], value)),
+        }
+    }
+
+    /// Resolve the dependencies of the target node, as `resolve` does, but first check
+    /// that no node the target depends upon (directly or transitively, including below
+    /// satisfied nodes) is a phantom node (see `phantom_nodes`), returning
+    /// `SolventError::NoSuchNode` if any is. Target node must exist. Use `resolve_strict`
+    /// to learn which nodes are phantoms.
+    pub fn resolve_validated<'a>(&'a self, target: &T) -> Result<Vec<&'a T>, SolventError> {
+        let pos = self._pos(target).ok_or(SolventError::NoSuchNode)?;
+        if !self._reachable_phantoms(pos).is_empty() {
+            return Err(SolventError::NoSuchNode);
+        }
+        Ok(self
+            ._resolve(pos)?
+            .into_iter()
+            .map(|n| &self.nodes[n])
+            .collect())
+    }
+
+    /// Resolve the dependencies of the target node, as `dependencies_of` does, collecting
+    /// as many nodes as could be resolved before an error occurred along with the error,
+    /// if any.
+    pub fn resolve_partial(&self, target: &T) -> (Vec<&T>, Option<SolventError>) {
+        let mut resolved = Vec::new();
+        let iter = match self.dependencies_of(target) {
+            Ok(iter) => iter,
+            Err(e) => return (resolved, Some(e)),
+        };
+        for node in iter {
+            match node {
+                Ok(n) => resolved.push(n),
+                Err(e) => return (resolved, Some(e)),
+            }
+        }
+        (resolved, None)
+    }
+
+    /// Call `f` on each dependency of the target node (and finally the target itself) in
+    /// the order `dependencies_of` would output them. If `f` returns an error, resolution
+    /// stops and that error is returned. Target node must exist.
+    pub fn for_each_dependency<F, E>(&self, target: &T, mut f: F) -> Result<(), ResolveOrUser<E>>
+    where
+        F: FnMut(&T) -> Result<(), E>,
+    {
+        let iter = self
+            .dependencies_of(target)
+            .map_err(ResolveOrUser::Resolve)?;
+        for node in iter {
+            let node = node.map_err(ResolveOrUser::Resolve)?;
+            f(node).map_err(ResolveOrUser::User)?;
+        }
+        Ok(())
+    }
+
+    /// Get a Resolver, which resolves the dependencies of multiple targets one after
+    /// another without outputting any node more than once.
+    pub fn resolver(&self) -> Resolver<'_, T> {
+        Resolver {
+            depgraph: self,
+            emitted: HashSet::new(),
+        }
+    }
+
+    /// Get the dependencies of the target node which are at most `max_hops` dependency
+    /// edges away from it (the target itself is zero hops away). They are returned in an
+    /// order such that each node's dependencies within that set precede it. Satisfied
+    /// nodes are skipped. Target node must exist.
+    pub fn dependencies_within<'a>(
+        &'a self,
+        target: &T,
+        max_hops: usize,
+    ) -> Result<Vec<&'a T>, SolventError> {
+        let pos = self._pos(target).ok_or(SolventError::NoSuchNode)?;
+
+        // Breadth first search to find the shortest distance of each node
+        let mut within: HashSet<usize> = HashSet::new();
+        let mut queue: VecDeque<(usize, usize)> = VecDeque::new();
+        if !self.satisfied.contains(&pos) {
+            within.insert(pos);
+            queue.push_back((pos, 0));
+        }
+        while let Some((n, hops)) = queue.pop_front() {
+            if hops == max_hops {
+                continue;
+            }
+            if let Some(deplist) = self.dependencies.get(&n) {
+                for dep in deplist.iter() {
+                    if !self.satisfied.contains(dep) && within.insert(*dep) {
+                        queue.push_back((*dep, hops + 1));
+                    }
+                }
+            }
+        }
+
+        let mut visited = HashSet::new();
+        let mut order = Vec::new();
+        if within.contains(&pos) {
+            self._visit(pos, &|n| !within.contains(&n), &mut visited, &mut order)?;
+        }
+        Ok(order.into_iter().map(|n| &self.nodes[n]).collect())
+    }
+
+    /// Resolve every root of the graph (every node which nothing depends upon) together,
+    /// as when building all targets: each unsatisfied node is output once, after all of
+    /// its dependencies. Roots are resolved in the order they were registered. Nodes only
+    /// reachable through a cycle are still visited, so such a cycle is reported.
+    pub fn resolve_everything(&self) -> Result<Vec<&T>, SolventError> {
+        let depended_upon: HashSet<usize> = self.dependencies.values().flatten().cloned().collect();
+        let roots = (0..self.nodes.len()).filter(|n| !depended_upon.contains(n));
+
+        let skip = |n| self.satisfied.contains(&n);
+        let mut visited = HashSet::new();
+        let mut order = Vec::new();
+        for pos in roots.chain(0..self.nodes.len()) {
+            self._visit(pos, &skip, &mut visited, &mut order)?;
+        }
+        Ok(order.into_iter().map(|n| &self.nodes[n]).collect())
+    }
+
+    /// Get all of the unsatisfied nodes in the graph in an order such that each node
+    /// follows all of its dependencies.
+    pub fn topological_sort(&self) -> Result<Vec<&T>, SolventError> {
+        Ok(self
+            ._topological_order()?
+            .into_iter()
+            .map(|pos| &self.nodes[pos])
+            .collect())
+    }
+
+    /// Get all of the unsatisfied nodes in the graph in an order such that each node
+    /// precedes all of its dependencies (i.e. follows all of its dependents). This is the
+    /// reverse of `topological_sort`, and is suitable for tearing things down.
+    pub fn reverse_topological_sort(&self) -> Result<Vec<&T>, SolventError> {
+        let mut order = self.topological_sort()?;
+        order.reverse();
+        Ok(order)
+    }
+
+    /// Consume the DepGraph, returning all of its unsatisfied nodes in an order such that
+    /// each node follows all of its dependencies.
+    pub fn into_topological_order(self) -> Result<Vec<T>, SolventError> {
+        let order = self._topological_order()?;
+        let mut nodes: Vec<Option<T>> = self.nodes.into_iter().map(Some).collect();
+        Ok(order
+            .into_iter()
+            .filter_map(|pos| nodes[pos].take())
+            .collect())
+    }
+
+    /// Consume the DepGraph, returning an iterator which moves its unsatisfied nodes out
+    /// one at a time, in the same order as `into_topological_order`. Each node is found
+    /// as it is asked for, so a caller stopping early does no more work than needed. If a
+    /// cycle is detected the iterator yields the error after the nodes before it.
+    pub fn into_iter_topological(self) -> impl Iterator<Item = Result<T, SolventError>> {
+        IntoIterTopological {
+            nodes: self.nodes.into_iter().map(Some).collect(),
+            dependencies: self.dependencies,
+            satisfied: self.satisfied,
+            curpath: Vec::new(),
+            target: 0,
+            halted: false,
+        }
+    }
+
+    /// Resolve the dependencies of the target node in waves. Every node in a wave depends
+    /// only upon nodes in earlier waves (or satisfied nodes), so the nodes within a wave
+    /// may be processed in any order or in parallel. The target is in the last wave.
+    /// Target node must exist.
+    pub fn resolve_in_waves<'a>(&'a self, target: &T) -> Result<Vec<Vec<&'a T>>, SolventError> {
+        let pos = self._pos(target).ok_or(SolventError::NoSuchNode)?;
+        let order = self._resolve(pos)?;
+
+        // Each node is one wave after its latest dependency. Dependencies precede
+        // dependents in `order`, so their waves are known by the time we need them.
+        let mut wave_of: HashMap<usize, usize> = HashMap::new();
+        let mut waves: Vec<Vec<&'a T>> = Vec::new();
+        for n in order.into_iter() {
+            let wave = self
+                .dependencies
+                .get(&n)
+                .into_iter()
+                .flat_map(|deplist| deplist.iter())
+                .filter_map(|dep| wave_of.get(dep))
+                .map(|w| w + 1)
+                .max()
+                .unwrap_or(0);
+            wave_of.insert(n, wave);
+            if waves.len() <= wave {
+                waves.resize_with(wave + 1, Vec::new);
+            }
+            waves[wave].push(&self.nodes[n]);
+        }
+
+        Ok(waves)
+    }
+
+    /// Resolve the dependencies of the target node in waves, as `resolve_in_waves` does,
+    /// and split each wave into chunks of at most `chunk_size` nodes. The nodes within a
+    /// chunk, and the chunks within a wave, may be processed in any order. A `chunk_size`
+    /// of 0 is treated as 1. Target node must exist.
+    pub fn resolve_chunks<'a>(
+        &'a self,
+        target: &T,
+        chunk_size: usize,
+    ) -> Result<Vec<Vec<&'a T>>, SolventError> {
+        Ok(self
+            .resolve_in_waves(target)?
+            .iter()
+            .flat_map(|wave| wave.chunks(chunk_size.max(1)).map(|chunk| chunk.to_vec()))
+            .collect())
+    }
+
+    /// Resolve the dependencies of all of the `targets` together, treating each node in
+    /// `stop_at` as a boundary: boundary nodes are output, but their dependencies are not
+    /// descended into (unless reachable some other way). Each node is output once, after
+    /// its dependencies. All nodes must exist.
+    pub fn resolve_with_boundaries(
+        &self,
+        targets: &[T],
+        stop_at: &[T],
+    ) -> Result<Vec<&T>, SolventError> {
+        let target_poses = self._poses(targets)?;
+        let stop_poses: HashSet<usize> = self._poses(stop_at)?.into_iter().collect();
+
+        let skip = |n| self.satisfied.contains(&n);
+        let stop = |n| stop_poses.contains(&n);
+        let mut visited = HashSet::new();
+        let mut order = Vec::new();
+        for pos in target_poses.into_iter() {
+            self._visit_until(pos, &skip, &stop, &mut visited, &mut order)?;
+        }
+        Ok(order.into_iter().map(|n| &self.nodes[n]).collect())
+    }
+
+    /// Resolve the dependencies of the target node as `resolve` does, but hold back each
+    /// of the `pinned` nodes for as long as possible: a pinned node is only output once
+    /// nothing else can be, i.e. once every remaining node depends upon a pinned node.
+    /// The result is still a valid order. All nodes must exist.
+    pub fn resolve_with_pinned_last<'a>(
+        &'a self,
+        target: &T,
+        pinned: &[T],
+    ) -> Result<Vec<&'a T>, SolventError> {
+        let pos = self._pos(target).ok_or(SolventError::NoSuchNode)?;
+        let pinned: HashSet<usize> = self._poses(pinned)?.into_iter().collect();
+        let mut remaining = self._resolve(pos)?;
+
+        // Repeatedly take the first ready unpinned node in resolution order. When there is
+        // none, release the ready pinned node which the most remaining unpinned nodes need
+        // (the first in resolution order, if several are needed equally), so that pinned
+        // nodes needed by fewer nodes are held back longer.
+        let mut emitted: HashSet<usize> = HashSet::new();
+        let mut order = Vec::with_capacity(remaining.len());
+        while !remaining.is_empty() {
+            let ready = |n: &usize| {
+                self._deps_of(*n)
+                    .all(|dep| emitted.contains(&dep) || self.satisfied.contains(&dep))
+            };
+            let i = match remaining
+                .iter()
+                .position(|n| !pinned.contains(n) && ready(n))
+            {
+                Some(i) => i,
+                None => {
+                    let mut needed_by: HashMap<usize, usize> = HashMap::new();
+                    for n in remaining.iter().filter(|n| !pinned.contains(*n)) {
+                        let mut seen: HashSet<usize> = HashSet::new();
+                        let mut stack = vec![*n];
+                        while let Some(m) = stack.pop() {
+                            stack.extend(self._deps_of(m).filter(|dep| {
+                                !emitted.contains(dep)
+                                    && !self.satisfied.contains(dep)
+                                    && seen.insert(*dep)
+                            }));
+                        }
+                        for dep in seen.into_iter().filter(|dep| pinned.contains(dep)) {
+                            *needed_by.entry(dep).or_default() += 1;
+                        }
+                    }
+                    let mut best: Option<(usize, usize)> = None;
+                    for (i, n) in remaining.iter().enumerate().filter(|(_, n)| ready(n)) {
+                        let count = needed_by.get(n).cloned().unwrap_or(0);
+                        if best.map_or(true, |(_, most)| count > most) {
+                            best = Some((i, count));
+                        }
+                    }
+                    best.expect("a resolution order always has a ready node").0
+                }
+            };
+            let n = remaining.remove(i);
+            emitted.insert(n);
+            order.push(n);
+        }
+        Ok(order.into_iter().map(|n| &self.nodes[n]).collect())
+    }
+
+    /// Resolve the dependencies of the target node as `resolve` does, also reporting how
+    /// much work the walk did (see `ResolveMetrics`). Target node must exist.
+    pub fn resolve_with_metrics<'a>(
+        &'a self,
+        target: &T,
+    ) -> Result<(Vec<&'a T>, ResolveMetrics), SolventError> {
+        let pos = self._pos(target).ok_or(SolventError::NoSuchNode)?;
+        let skip = |n| self.satisfied.contains(&n);
+        let mut visited = HashSet::new();
+        let mut curpath = Vec::new();
+        let mut order = Vec::new();
+        let mut metrics = ResolveMetrics::default();
+        self._visit_inner(
+            pos,
+            &skip,
+            &|_| false,
+            &mut visited,
+            &mut curpath,
+            &mut order,
+            &mut metrics,
+        )?;
+        let order = self._apply_hints(order);
+        Ok((order.into_iter().map(|n| &self.nodes[n]).collect(), metrics))
+    }
+
+    /// Resolve the dependencies of all of the `targets`, taking one node from each target's
+    /// resolution in turn rather than finishing one target before starting the next.
+    /// Nodes needed by more than one target are output once. Every node still follows
+    /// its dependencies. All nodes must exist.
+    pub fn resolve_round_robin(&self, targets: &[T]) -> Result<Vec<&T>, SolventError> {
+        let mut queues: Vec<VecDeque<usize>> = Vec::with_capacity(targets.len());
+        for pos in self._poses(targets)?.into_iter() {
+            queues.push(self._resolve(pos)?.into());
+        }
+
+        let mut emitted: HashSet<usize> = HashSet::new();
+        let mut order = Vec::new();
+        while queues.iter().any(|queue| !queue.is_empty()) {
+            for queue in queues.iter_mut() {
+                // Each target's dependencies precede it in its own resolution, so they
+                // have all been output by the time it is reached
+                while let Some(n) = queue.pop_front() {
+                    if emitted.insert(n) {
+                        order.push(n);
+                        break;
+                    }
+                }
+            }
+        }
+        Ok(order.into_iter().map(|n| &self.nodes[n]).collect())
+    }
+
+    // Positions of each of the nodes, all of which must exist
+    fn _poses(&self, nodes: &[T]) -> Result<Vec<usize>, SolventError> {
+        nodes
+            .iter()
+            .map(|node| self._pos(node).ok_or(SolventError::NoSuchNode))
+            .collect()
+    }
+
+    /// Check whether `order` is a valid resolution of the target node: it must contain
+    /// exactly the nodes that `dependencies_of(target)` would output, each exactly once,
+    /// with every node following its unsatisfied dependencies. Target node must exist.
+    pub fn is_valid_order(&self, target: &T, order: &[T]) -> Result<bool, SolventError> {
+        let pos = self._pos(target).ok_or(SolventError::NoSuchNode)?;
+        let required: HashSet<usize> = self._resolve(pos)?.into_iter().collect();
+        if order.len() != required.len() {
+            return Ok(false);
+        }
+
+        let mut seen: HashSet<usize> = HashSet::new();
+        for node in order.iter() {
+            let node_pos = match self._pos(node) {
+                Some(p) if required.contains(&p) => p,
+                _ => return Ok(false),
+            };
+            if let Some(deplist) = self.dependencies.get(&node_pos) {
+                let ready = deplist
+                    .iter()
+                    .all(|d| self.satisfied.contains(d) || seen.contains(d));
+                if !ready {
+                    return Ok(false);
+                }
+            }
+            if !seen.insert(node_pos) {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Get the critical nodes of the target node: those dependencies which lie on every
+    /// dependency path from the target to some other dependency, so that removing one of
+    /// them would disconnect the target from part of its dependencies. These are the
+    /// dominators within the target's dependencies, excluding the target itself. Satisfied
+    /// nodes are not taken into account. Target node must exist.
+    pub fn critical_nodes<'a>(&'a self, target: &T) -> Result<Vec<&'a T>, SolventError> {
+        let pos = self._pos(target).ok_or(SolventError::NoSuchNode)?;
+        let order = self._postorder(pos)?;
+
+        // Walk from the target downwards so that each node's dependents are handled
+        // before it, intersecting the dominators along every incoming path.
+        let mut dominators: HashMap<usize, HashSet<usize>> = HashMap::new();
+        for n in order.iter().rev() {
+            let mut doms = dominators.remove(n).unwrap_or_default();
+            doms.insert(*n);
+            if let Some(deplist) = self.dependencies.get(n) {
+                for dep in deplist.iter() {
+                    let dep_doms = match dominators.get(dep) {
+                        Some(existing) => existing
+                            .iter()
+                            .filter(|d| doms.contains(*d))
+                            .cloned()
+                            .collect(),
+                        None => doms.clone(),
+                    };
+                    dominators.insert(*dep, dep_doms);
+                }
+            }
+            dominators.insert(*n, doms);
+        }
+
+        let mut critical: HashSet<usize> = HashSet::new();
+        for (n, doms) in dominators.iter() {
+            critical.extend(doms.iter().filter(|d| **d != *n && **d != pos));
+        }
+
+        Ok(order
+            .into_iter()
+            .filter(|n| critical.contains(n))
+            .map(|n| &self.nodes[n])
+            .collect())
+    }
+
+    /// Get the transitively redundant dependency edges: those `(node, depends_on)` edges
+    /// for which `depends_on` is also reachable from `node` through some other dependency.
+    /// The graph is not modified. Satisfied nodes are not taken into account.
+    pub fn redundant_edges(&self) -> Result<Vec<(&T, &T)>, SolventError> {
+        let order = self._structural_order()?;
+
+        // The set of nodes reachable from each node, built up from the leaves
+        let mut reachable: HashMap<usize, HashSet<usize>> = HashMap::new();
+        let mut redundant = Vec::new();
+        for n in order.into_iter() {
+            let mut reach: HashSet<usize> = HashSet::new();
+            if let Some(deplist) = self.dependencies.get(&n) {
+                for dep in deplist.iter() {
+                    let covered = deplist
+                        .iter()
+                        .any(|other| other != dep && reachable[other].contains(dep));
+                    if covered {
+                        redundant.push((&self.nodes[n], &self.nodes[*dep]));
+                    }
+                }
+                for dep in deplist.iter() {
+                    reach.insert(*dep);
+                    reach.extend(reachable[dep].iter());
+                }
+            }
+            reachable.insert(n, reach);
+        }
+
+        Ok(redundant)
+    }
+
+    /// Get the node with the most direct dependencies, along with how many it has. Ties go
+    /// to the node registered first. Returns `None` if the graph is empty.
+    pub fn max_out_degree_node(&self) -> Option<(&T, usize)> {
+        let degree = |n: &usize| self.dependencies.get(n).map_or(0, |deplist| deplist.len());
+        (0..self.nodes.len())
+            .map(|n| (degree(&n), n))
+            .max_by_key(|(degree, n)| (*degree, core::cmp::Reverse(*n)))
+            .map(|(degree, n)| (&self.nodes[n], degree))
+    }
+
+    /// Check that no node has more than `limit` direct dependencies, returning the first
+    /// node (in registration order) which does, along with how many it has.
+    pub fn enforce_max_out_degree(&self, limit: usize) -> Result<(), (&T, usize)> {
+        let degree = |n: &usize| self.dependencies.get(n).map_or(0, |deplist| deplist.len());
+        match (0..self.nodes.len()).find(|n| degree(n) > limit) {
+            Some(n) => Err((&self.nodes[n], degree(&n))),
+            None => Ok(()),
+        }
+    }
+
+    /// Get the node with the most direct dependents, along with how many it has. Ties go
+    /// to the node registered first. Returns `None` if the graph is empty.
+    pub fn max_in_degree_node(&self) -> Option<(&T, usize)> {
+        let mut degrees = vec![0usize; self.nodes.len()];
+        for dep in self.dependencies.values().flatten() {
+            degrees[*dep] += 1;
+        }
+        degrees
+            .into_iter()
+            .enumerate()
+            .max_by_key(|(n, degree)| (*degree, core::cmp::Reverse(*n)))
+            .map(|(n, degree)| (&self.nodes[n], degree))
+    }
+
+    /// Estimate how many dependencies would have to be removed to make the graph acyclic
+    /// (the size of a feedback arc set), using the greedy heuristic of Eades, Lin and
+    /// Smyth. The estimate is not necessarily minimal, but is always 0 for an acyclic
+    /// graph. Satisfied nodes are not taken into account.
+    pub fn approx_feedback_arcs(&self) -> usize {
+        let len = self.nodes.len();
+        let mut out_degree = vec![0usize; len];
+        let mut in_degree = vec![0usize; len];
+        let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); len];
+        let mut self_loops = 0;
+        for (n, deplist) in self.dependencies.iter() {
+            for dep in deplist.iter() {
+                if n == dep {
+                    self_loops += 1;
+                } else {
+                    out_degree[*n] += 1;
+                    in_degree[*dep] += 1;
+                    dependents[*dep].push(*n);
+                }
+            }
+        }
+
+        // Peel off sinks to the back and sources to the front, otherwise move the node
+        // with the greatest excess of outgoing over incoming edges to the front
+        let mut removed = vec![false; len];
+        let mut front = Vec::with_capacity(len);
+        let mut back = Vec::new();
+        for _ in 0..len {
+            let remaining = (0..len).filter(|n| !removed[*n]);
+            let (n, is_sink) = match remaining.clone().find(|n| out_degree[*n] == 0) {
+                Some(n) => (n, true),
+                None => match remaining.clone().find(|n| in_degree[*n] == 0) {
+                    Some(n) => (n, false),
+                    None => {
+                        let n = remaining
+                            .max_by_key(|n| out_degree[*n] as isize - in_degree[*n] as isize)
+                            .expect("a node remains");
+                        (n, false)
+                    }
+                },
+            };
+            removed[n] = true;
+            for dep in self._deps_of(n).filter(|dep| *dep != n) {
+                in_degree[dep] -= 1;
+            }
+            for dependent in dependents[n].iter() {
+                out_degree[*dependent] -= 1;
+            }
+            if is_sink {
+                back.push(n);
+            } else {
+                front.push(n);
+            }
+        }
+
+        let mut position = vec![0usize; len];
+        for (i, n) in front.into_iter().chain(back.into_iter().rev()).enumerate() {
+            position[n] = i;
+        }
+        let backward = self
+            .dependencies
+            .iter()
+            .flat_map(|(n, deplist)| deplist.iter().map(move |d| (*n, *d)))
+            .filter(|(n, d)| n != d && position[*d] < position[*n])
+            .count();
+        backward + self_loops
+    }
+
+    /// Find groups of two or more nodes which have exactly the same direct dependencies,
+    /// as candidates for merging. Nodes without dependencies are not grouped. Groups are
+    /// ordered by their first node, and nodes within a group by when they were registered.
+    pub fn equivalent_groups(&self) -> Vec<Vec<&T>> {
+        let mut group_of: HashMap<Vec<usize>, usize> = HashMap::new();
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        for n in 0..self.nodes.len() {
+            let mut deps: Vec<usize> = self._deps_of(n).collect();
+            if deps.is_empty() {
+                continue;
+            }
+            deps.sort_unstable();
+            let g = *group_of.entry(deps).or_insert_with(|| {
+                groups.push(Vec::new());
+                groups.len() - 1
+            });
+            groups[g].push(n);
+        }
+
+        groups
+            .into_iter()
+            .filter(|group| group.len() > 1)
+            .map(|group| group.into_iter().map(|n| &self.nodes[n]).collect())
+            .collect()
+    }
+
+    /// Group the nodes of the graph into connected components, treating dependencies as
+    /// undirected edges. Nodes in different components have no dependency relationship
+    /// whatsoever, and so can be handled independently.
+    pub fn connected_components(&self) -> Vec<Vec<&T>> {
+        self._connected_components()
+            .into_iter()
+            .map(|component| component.into_iter().map(|n| &self.nodes[n]).collect())
+            .collect()
+    }
+
+    /// Group the nodes of the graph into strongly connected components (sets of nodes
+    /// which all depend upon each other, directly or indirectly), ordered so that each
+    /// component follows every component it depends upon. Nodes which are not part of any
+    /// cycle are components of their own. This allows a cyclic graph to be processed one
+    /// component at a time. Satisfied nodes are included.
+    pub fn scc_order(&self) -> Vec<Vec<&T>> {
+        self._strongly_connected_components()
+            .into_iter()
+            .map(|component| component.into_iter().map(|n| &self.nodes[n]).collect())
+            .collect()
+    }
+
+    /// Resolve each connected component of the graph (see `connected_components`)
+    /// separately, returning the unsatisfied nodes of each component in an order such that
+    /// each node follows its dependencies. Components are returned in the same order as
+    /// `connected_components` returns them, even if all of their nodes are satisfied.
+    pub fn resolve_components(&self) -> Result<Vec<Vec<&T>>, SolventError> {
+        let skip = |n| self.satisfied.contains(&n);
+        let mut visited = HashSet::new();
+        let mut components = Vec::new();
+        for component in self._connected_components().into_iter() {
+            let mut order = Vec::new();
+            for pos in component.into_iter() {
+                self._visit(pos, &skip, &mut visited, &mut order)?;
+            }
+            components.push(order.into_iter().map(|n| &self.nodes[n]).collect());
+        }
+        Ok(components)
+    }
+
+    /// Get a short multi-line human readable summary of the graph, for logging: the
+    /// number of nodes and dependencies, the number of roots (nodes nothing depends upon)
+    /// and leaves (nodes without dependencies), whether the graph is acyclic, and the
+    /// number of nodes in its longest dependency chain.
+    pub fn describe(&self) -> String {
+        let edges: usize = self
+            .dependencies
+            .values()
+            .map(|deplist| deplist.len())
+            .sum();
+        let depended_upon: HashSet<usize> = self.dependencies.values().flatten().cloned().collect();
+        let roots = (0..self.nodes.len())
+            .filter(|n| !depended_upon.contains(n))
+            .count();
+        let leaves = (0..self.nodes.len())
+            .filter(|n| {
+                self.dependencies
+                    .get(n)
+                    .map_or(true, |deplist| deplist.is_empty())
+            })
+            .count();
+        let generations = self._generations().ok();
+        let longest_chain = match generations {
+            Some(ref generations) => generations
+                .values()
+                .max()
+                .map_or(0, |generation| generation + 1)
+                .to_string(),
+            None => "n/a".to_string(),
+        };
+        format!(
+            "nodes: {}\nedges: {}\nroots: {}\nleaves: {}\nacyclic: {}\nlongest chain: {}",
+            self.nodes.len(),
+            edges,
+            roots,
+            leaves,
+            generations.is_some(),
+            longest_chain
+        )
+    }
+
+    fn _connected_components(&self) -> Vec<Vec<usize>> {
+        // Union-find over node positions
+        fn find(parent: &mut [usize], mut n: usize) -> usize {
+            while parent[n] != n {
+                parent[n] = parent[parent[n]];
+                n = parent[n];
+            }
+            n
+        }
+
+        let mut parent: Vec<usize> = (0..self.nodes.len()).collect();
+        for (n, deplist) in self.dependencies.iter() {
+            for dep in deplist.iter() {
+                let a = find(&mut parent, *n);
+                let b = find(&mut parent, *dep);
+                parent[a.max(b)] = a.min(b);
+            }
+        }
+
+        let mut component_of: HashMap<usize, usize> = HashMap::new();
+        let mut components: Vec<Vec<usize>> = Vec::new();
+        for n in 0..self.nodes.len() {
+            let root = find(&mut parent, n);
+            let c = *component_of.entry(root).or_insert_with(|| {
+                components.push(Vec::new());
+                components.len() - 1
+            });
+            components[c].push(n);
+        }
+        components
+    }
+
+    // The strongly connected components of the graph (by Tarjan's algorithm), each with
+    // its node positions in ascending order. A component is listed after every component
+    // it depends upon.
+    fn _strongly_connected_components(&self) -> Vec<Vec<usize>> {
+        struct Tarjan {
+            next_index: usize,
+            index: Vec<Option<usize>>,
+            lowlink: Vec<usize>,
+            stack: Vec<usize>,
+            on_stack: Vec<bool>,
+            components: Vec<Vec<usize>>,
+        }
+
+        fn connect<T: Eq>(depgraph: &DepGraph<T>, state: &mut Tarjan, n: usize) {
+            state.index[n] = Some(state.next_index);
+            state.lowlink[n] = state.next_index;
+            state.next_index += 1;
+            state.stack.push(n);
+            state.on_stack[n] = true;
+
+            for dep in depgraph._deps_of(n) {
+                match state.index[dep] {
+                    None => {
+                        connect(depgraph, state, dep);
+                        state.lowlink[n] = state.lowlink[n].min(state.lowlink[dep]);
+                    }
+                    Some(index) if state.on_stack[dep] => {
+                        state.lowlink[n] = state.lowlink[n].min(index);
+                    }
+                    Some(_) => {}
+                }
+            }
+
+            if Some(state.lowlink[n]) == state.index[n] {
+                let mut component = Vec::new();
+                while let Some(m) = state.stack.pop() {
+                    state.on_stack[m] = false;
+                    component.push(m);
+                    if m == n {
+                        break;
+                    }
+                }
+                component.sort_unstable();
+                state.components.push(component);
+            }
+        }
+
+        let len = self.nodes.len();
+        let mut state = Tarjan {
+            next_index: 0,
+            index: vec![None; len],
+            lowlink: vec![0; len],
+            stack: Vec::new(),
+            on_stack: vec![false; len],
+            components: Vec::new(),
+        };
+        for n in 0..len {
+            if state.index[n].is_none() {
+                connect(self, &mut state, n);
+            }
+        }
+        state.components
+    }
+
+    /// Get the generation of every node in the graph: the length of the longest chain of
+    /// dependencies below it. Nodes without dependencies are generation 0. Satisfied nodes
+    /// are not taken into account. Nodes are listed in the order they were registered, as
+    /// pairs rather than a HashMap, since which HashMap is used depends upon the `alloc`
+    /// feature.
+    pub fn generations(&self) -> Result<Vec<(&T, usize)>, SolventError> {
+        let generations = self._generations()?;
+        Ok((0..self.nodes.len())
+            .map(|n| (&self.nodes[n], generations[&n]))
+            .collect())
+    }
+
+    /// Get all of the nodes of the given generation (see `generations`), in the order they
+    /// were registered. Satisfied nodes are not taken into account.
+    pub fn nodes_at_generation(&self, generation: usize) -> Result<Vec<&T>, SolventError> {
+        let generations = self._generations()?;
+        Ok((0..self.nodes.len())
+            .filter(|n| generations[n] == generation)
+            .map(|n| &self.nodes[n])
+            .collect())
+    }
+
+    // The generation of each node position: 0 for nodes without dependencies, otherwise
+    // one more than the greatest generation amongst its dependencies.
+    fn _generations(&self) -> Result<HashMap<usize, usize>, SolventError> {
+        let mut generations: HashMap<usize, usize> = HashMap::new();
+        for n in self._structural_order()?.into_iter() {
+            let generation = self
+                .dependencies
+                .get(&n)
+                .into_iter()
+                .flatten()
+                .map(|dep| generations[dep] + 1)
+                .max()
+                .unwrap_or(0);
+            generations.insert(n, generation);
+        }
+        Ok(generations)
+    }
+
+    // Positions of all nodes, satisfied or not, ordered such that each follows its
+    // dependencies
+    fn _structural_order(&self) -> Result<Vec<usize>, SolventError> {
+        let mut visited = HashSet::new();
+        let mut order = Vec::new();
+        for pos in 0..self.nodes.len() {
+            self._visit(pos, &|_| false, &mut visited, &mut order)?;
+        }
+        Ok(order)
+    }
+
+    // Positions of the unsatisfied dependencies of `pos` (and `pos` itself), in the
+    // order `dependencies_of` would output them
+    fn _resolve(&self, pos: usize) -> Result<Vec<usize>, SolventError> {
+        let mut visited = HashSet::new();
+        let mut order = Vec::new();
+        let skip = |n| self.satisfied.contains(&n);
+        self._visit(pos, &skip, &mut visited, &mut order)?;
+        Ok(self._apply_hints(order))
+    }
+
+    // Positions of all dependencies of `pos` (and `pos` itself), satisfied or not, ordered
+    // such that each follows its dependencies
+    fn _postorder(&self, pos: usize) -> Result<Vec<usize>, SolventError> {
+        let mut visited = HashSet::new();
+        let mut order = Vec::new();
+        self._visit(pos, &|_| false, &mut visited, &mut order)?;
+        Ok(order)
+    }
+
+    // Positions of all unsatisfied nodes, ordered such that each follows its dependencies
+    fn _topological_order(&self) -> Result<Vec<usize>, SolventError> {
+        let mut visited = HashSet::new();
+        let mut order = Vec::new();
+        let skip = |n| self.satisfied.contains(&n);
+        for pos in 0..self.nodes.len() {
+            self._visit(pos, &skip, &mut visited, &mut order)?;
+        }
+        Ok(order)
+    }
+
+    // Depth first postorder walk of the dependencies of `pos`, appending nodes to `order`
+    // such that each node follows its dependencies. Nodes for which `skip` returns true
+    // are neither output nor descended into. Nodes already in `visited` are not output
+    // again.
+    fn _visit(
+        &self,
+        pos: usize,
+        skip: &dyn Fn(usize) -> bool,
+        visited: &mut HashSet<usize>,
+        order: &mut Vec<usize>,
+    ) -> Result<(), SolventError> {
+        self._visit_until(pos, skip, &|_| false, visited, order)
+    }
+
+    // As `_visit`, except that nodes for which `stop` returns true are output but not
+    // descended into.
+    fn _visit_until(
+        &self,
+        pos: usize,
+        skip: &dyn Fn(usize) -> bool,
+        stop: &dyn Fn(usize) -> bool,
+        visited: &mut HashSet<usize>,
+        order: &mut Vec<usize>,
+    ) -> Result<(), SolventError> {
+        let mut curpath = Vec::new();
+        let mut metrics = ResolveMetrics::default();
+        self._visit_inner(pos, skip, stop, visited, &mut curpath, order, &mut metrics)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn _visit_inner(
+        &self,
+        pos: usize,
+        skip: &dyn Fn(usize) -> bool,
+        stop: &dyn Fn(usize) -> bool,
+        visited: &mut HashSet<usize>,
+        curpath: &mut Vec<usize>,
+        order: &mut Vec<usize>,
+        metrics: &mut ResolveMetrics,
+    ) -> Result<(), SolventError> {
+        metrics.visits += 1;
+        if curpath.contains(&pos) {
+            let s = format!("{:?}", curpath);
+            return Err(SolventError::CycleDetected(s));
+        }
+        if visited.contains(&pos) {
+            return Ok(());
+        }
+        if skip(pos) {
+            metrics.prunes += 1;
+            return Ok(());
+        }
+        curpath.push(pos);
+        metrics.max_depth = metrics.max_depth.max(curpath.len());
+        if !stop(pos) {
+            if let Some(deplist) = self.dependencies.get(&pos) {
+                for dep in deplist.iter() {
+                    self._visit_inner(*dep, skip, stop, visited, curpath, order, metrics)?;
+                }
+            }
+        }
+        curpath.pop();
+        visited.insert(pos);
+        order.push(pos);
+        Ok(())
+    }
+}
+
+impl DepGraph<String> {
+    /// Build a DepGraph from lines of the form `node: dep1 dep2 ...`, meaning `node`
+    /// depends on each of the whitespace separated dependencies. Blank lines and lines
+    /// starting with `#` are ignored.
+    pub fn from_edge_lines(input: &str) -> Result<DepGraph<String>, SolventError> {
+        let mut depgraph = DepGraph::new();
+        for (i, line) in input.lines().enumerate() {
+            depgraph._parse_edge_line(i + 1, line)?;
+        }
+        Ok(depgraph)
+    }
+
+    /// Build a DepGraph from lines read from `r`, in the same format as `from_edge_lines`.
+    /// The input is processed line by line rather than being read all at once.
+    #[cfg(feature = "std")]
+    pub fn from_reader<R: std::io::Read>(r: R) -> Result<DepGraph<String>, SolventError> {
+        use std::io::BufRead;
+
+        let mut depgraph = DepGraph::new();
+        for (i, line) in std::io::BufReader::new(r).lines().enumerate() {
+            let line = line.map_err(|e| SolventError::ParseError(i + 1, e.to_string()))?;
+            depgraph._parse_edge_line(i + 1, &line)?;
+        }
+        Ok(depgraph)
+    }
+
+    fn _parse_edge_line(&mut self, line_number: usize, line: &str) -> Result<(), SolventError> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return Ok(());
+        }
+
+        let (node, deps) = line.split_once(':').ok_or_else(|| {
+            SolventError::ParseError(line_number, "expected `node: dependencies`".to_string())
+        })?;
+        let node = node.trim();
+        if node.is_empty() {
+            return Err(SolventError::ParseError(
+                line_number,
+                "missing node name".to_string(),
+            ));
+        }
+
+        self.register_dependencies(
+            node.to_string(),
+            deps.split_whitespace().map(|d| d.to_string()).collect(),
+        );
+        Ok(())
+    }
+}
+
+impl<T: Eq + Clone> DepGraph<T> {
+    /// Register each `(node, depends_on)` pair as a dependency, as `register_dependency`
+    /// does.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the graph was created with `new_acyclic` and the dependencies would
+    /// create a cycle, in which case none are registered. Use `try_register_edges` to
+    /// handle this as an error.
+    pub fn register_edges(&mut self, edges: &[(T, T)]) {
+        if let Err(e) = self.try_register_edges(edges) {
+            panic!("Dependency would create a cycle: {}", e);
+        }
+    }
+
+    /// Register each `(node, depends_on)` pair as a dependency, as `register_edges` does,
+    /// unless the graph was created with `new_acyclic` and the dependencies would create a
+    /// cycle, in which case `SolventError::CycleDetected` is returned and none are
+    /// registered.
+    pub fn try_register_edges(&mut self, edges: &[(T, T)]) -> Result<(), SolventError> {
+        if self.acyclic.is_some() {
+            // Each edge is only checked against those before it once they are in place, so
+            // what was added for those is logged, to be taken out again if one fails.
+            let node_count = self.nodes.len();
+            let mut added: Vec<(usize, usize)> = Vec::new();
+            let mut new_entries: Vec<usize> = Vec::new();
+            let mut new_declared: Vec<usize> = Vec::new();
+            let mut undo = Vec::new();
+            for (node, depends_on) in edges.iter() {
+                let node_pos = self._register_node(node.clone());
+                let dep_pos = self._register_node(depends_on.clone());
+                if let Err(e) = self._order_dependencies(&[(node_pos, dep_pos)], &mut undo) {
+                    for (n, d) in added.into_iter() {
+                        if let Some(deplist) = self.dependencies.get_mut(&n) {
+                            deplist.remove(&d);
+                        }
+                    }
+                    for n in new_entries.into_iter() {
+                        self.dependencies.remove(&n);
+                    }
+                    for n in new_declared.into_iter() {
+                        self.declared.remove(&n);
+                    }
+                    self.nodes.truncate(node_count);
+                    if let Some(order) = self.acyclic.as_mut() {
+                        order.rollback(undo);
+                        order.truncate(node_count);
+                    }
+                    return Err(e);
+                }
+                if self.declared.insert(node_pos) {
+                    new_declared.push(node_pos);
+                }
+                if !self.dependencies.contains_key(&node_pos) {
+                    new_entries.push(node_pos);
+                }
+                if self._register_dependency(node_pos, dep_pos) {
+                    added.push((node_pos, dep_pos));
+                }
+            }
+        } else {
+            for (node, depends_on) in edges.iter() {
+                self.register_dependency(node.clone(), depends_on.clone());
+            }
+        }
+        Ok(())
+    }
+
+    /// Register `node -> depends_on` in this graph and `depends_on -> node` in `reverse`,
+    /// keeping a forward graph and its reverse graph in sync.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either graph was created with `new_acyclic` and the dependency would
+    /// create a cycle in it, in which case neither graph is changed.
+    pub fn add_and_reverse(&mut self, reverse: &mut DepGraph<T>, node: T, depends_on: T) {
+        let mut undo = Vec::new();
+        if let Err(e) =
+            self._check_dependencies(&node, core::slice::from_ref(&depends_on), &mut undo)
+        {
+            panic!("Dependency would create a cycle: {}", e);
+        }
+        if let Err(e) =
+            reverse._check_dependencies(&depends_on, core::slice::from_ref(&node), &mut Vec::new())
+        {
+            self._rollback_order(undo);
+            panic!("Dependency would create a cycle: {}", e);
+        }
+        reverse.register_dependency(depends_on.clone(), node.clone());
+        self.register_dependency(node, depends_on);
+    }
+
+    /// Get a new graph containing only the outstanding work for the target node: its
+    /// unsatisfied dependencies (and itself, if unsatisfied), along with the dependencies
+    /// amongst them. Satisfied nodes, and dependencies upon them, are left out. Target node
+    /// must exist.
+    pub fn outstanding_subgraph(&self, target: &T) -> Result<DepGraph<T>, SolventError> {
+        let pos = self._pos(target).ok_or(SolventError::NoSuchNode)?;
+        let order = self._resolve(pos)?;
+        let outstanding: HashSet<usize> = order.iter().cloned().collect();
+
+        let mut subgraph = DepGraph::new();
+        for n in order.iter() {
+            subgraph.register_node(self.nodes[*n].clone());
+        }
+        for n in order.iter() {
+            for dep in self._deps_of(*n).filter(|dep| outstanding.contains(dep)) {
+                subgraph.register_dependency(self.nodes[*n].clone(), self.nodes[dep].clone());
+            }
+        }
+        Ok(subgraph)
+    }
+
+    /// Get a new graph containing only the `targets` and the nodes they depend upon,
+    /// directly or indirectly, along with the dependencies amongst them. Unlike
+    /// `outstanding_subgraph`, satisfied nodes are kept and remain satisfied. Targets must
+    /// exist.
+    pub fn reduce_to(&self, targets: &[T]) -> Result<DepGraph<T>, SolventError> {
+        let mut reachable: HashSet<usize> = HashSet::new();
+        for target in targets.iter() {
+            let pos = self._pos(target).ok_or(SolventError::NoSuchNode)?;
+            if !reachable.contains(&pos) {
+                reachable.extend(self._dependencies_closure(pos));
+            }
+        }
+
+        let mut kept: Vec<usize> = reachable.into_iter().collect();
+        kept.sort_unstable();
+        let mut reduced = DepGraph::new();
+        for n in kept.iter() {
+            if self.declared.contains(n) {
+                reduced.register_node(self.nodes[*n].clone());
+            } else {
+                reduced._register_node(self.nodes[*n].clone());
+            }
+        }
+        // Nodes were registered in order, so kept[i] is at position i of the reduced graph
+        for (i, n) in kept.iter().enumerate() {
+            for dep in self._deps_of(*n) {
+                reduced.register_dependency(self.nodes[*n].clone(), self.nodes[dep].clone());
+            }
+            if self.satisfied.contains(n) {
+                reduced.satisfied.insert(i);
+            }
+        }
+        Ok(reduced)
+    }
+
+    /// Split the graph into at most `k` graphs which can be resolved independently, for
+    /// distributing work. Connected components are shared out so as to balance the number
+    /// of nodes in each graph. If there are fewer components than `k` and the graph is
+    /// acyclic, the largest components are split in two by generation, and the upper part
+    /// then holds the nodes of the lower part that it depends upon as satisfied boundary
+    /// nodes. Satisfied nodes remain satisfied. Returns no graphs if `k` is 0 or the graph
+    /// is empty.
+    pub fn partition(&self, k: usize) -> Vec<DepGraph<T>> {
+        let mut groups = self._connected_components();
+        if let Ok(generations) = self._generations() {
+            while groups.len() < k {
+                let largest = match (0..groups.len()).max_by_key(|i| groups[*i].len()) {
+                    Some(i) if groups[i].len() > 1 => i,
+                    _ => break,
+                };
+                // Every node has a greater generation than its dependencies, so the
+                // upper half only depends upon the lower half and not the other way round
+                let mut group = groups.swap_remove(largest);
+                group.sort_unstable_by_key(|n| (generations[n], *n));
+                let upper = group.split_off(group.len() / 2);
+                groups.push(group);
+                groups.push(upper);
+            }
+        }
+
+        groups.sort_by_key(|group| core::cmp::Reverse(group.len()));
+        // Never more bins than groups, however large `k` is
+        let mut bins: Vec<Vec<usize>> = vec![Vec::new(); k.min(groups.len())];
+        for group in groups.into_iter() {
+            if let Some(bin) = bins.iter_mut().min_by_key(|bin| bin.len()) {
+                bin.extend(group);
+            }
+        }
+
+        bins.into_iter()
+            .filter(|bin| !bin.is_empty())
+            .map(|mut bin| {
+                bin.sort_unstable();
+                let members: HashSet<usize> = bin.iter().cloned().collect();
+                let mut part = DepGraph::new();
+                for n in bin.iter() {
+                    if self.declared.contains(n) {
+                        part.register_node(self.nodes[*n].clone());
+                    } else {
+                        part._register_node(self.nodes[*n].clone());
+                    }
+                }
+                let mut satisfied: Vec<T> = Vec::new();
+                for n in bin.iter() {
+                    if self.satisfied.contains(n) {
+                        satisfied.push(self.nodes[*n].clone());
+                    }
+                    for dep in self._deps_of(*n) {
+                        part.register_dependency(self.nodes[*n].clone(), self.nodes[dep].clone());
+                        if !members.contains(&dep) {
+                            satisfied.push(self.nodes[dep].clone());
+                        }
+                    }
+                }
+                // All of these nodes have just been registered
+                let _ = part.mark_as_satisfied(&satisfied);
+                part
+            })
+            .collect()
+    }
+
+    /// Get the condensation of this graph: each strongly connected component (each set of
+    /// nodes which all depend upon each other, directly or indirectly) is collapsed into
+    /// a single node holding the component's values, and dependencies between components
+    /// are kept. The result is always acyclic. Satisfied state is not carried over.
+    pub fn condensation(&self) -> DepGraph<Vec<T>> {
+        let components = self._strongly_connected_components();
+        let mut component_of = vec![0; self.nodes.len()];
+        for (c, component) in components.iter().enumerate() {
+            for n in component.iter() {
+                component_of[*n] = c;
+            }
+        }
+
+        let mut dependencies: HashMap<usize, DepSet> = HashMap::new();
+        for (n, deplist) in self.dependencies.iter() {
+            for dep in deplist.iter() {
+                let (a, b) = (component_of[*n], component_of[*dep]);
+                if a != b {
+                    dependencies.entry(a).or_default().insert(b);
+                }
+            }
+        }
+
+        DepGraph {
+            nodes: components
+                .iter()
+                .map(|component| component.iter().map(|n| self.nodes[*n].clone()).collect())
+                .collect(),
+            dependencies,
+            satisfied: BitSet::new(),
+            tags: HashMap::new(),
+            declared: (0..components.len()).collect(),
+            acyclic: None,
+            aliases: Vec::new(),
+            hints: Vec::new(),
+        }
+    }
+
+    /// Get a copy of this graph with its internal representation rebuilt from scratch:
+    /// the same nodes, dependencies and state, but with each dependency set stored as
+    /// compactly as it would be if freshly registered (e.g. after many dependencies have
+    /// been removed).
+    pub fn rebuild(&self) -> DepGraph<T> {
+        let dependencies = self
+            .dependencies
+            .iter()
+            .filter(|(_, deplist)| !deplist.is_empty())
+            .map(|(n, deplist)| (*n, deplist.iter().cloned().collect()))
+            .collect();
+
+        DepGraph {
+            nodes: self.nodes.clone(),
+            dependencies,
+            satisfied: self.satisfied.iter().collect(),
+            tags: self.tags.clone(),
+            declared: self.declared.iter().cloned().collect(),
+            acyclic: self.acyclic.clone(),
+            aliases: self.aliases.clone(),
+            hints: self.hints.clone(),
+        }
+    }
+
+    /// Get a copy of this graph with every dependency reversed, so that if `a` depends on
+    /// `b` here then `b` depends on `a` in the result. Satisfied nodes remain satisfied.
+    ///
+    /// Nodes which have dependencies in the result are declared there, as are isolated
+    /// nodes which were declared here; every other node is a phantom of the result.
+    pub fn reversed(&self) -> DepGraph<T> {
+        let mut dependencies: HashMap<usize, DepSet> = HashMap::new();
+        let mut dependents: HashSet<usize> = HashSet::new();
+        for (n, deplist) in self.dependencies.iter() {
+            for dep in deplist.iter() {
+                dependencies.entry(*dep).or_default().insert(*n);
+                dependents.insert(*n);
+            }
+        }
+
+        let declared = (0..self.nodes.len())
+            .filter(|n| {
+                dependencies.contains_key(n)
+                    || (self.declared.contains(n) && !dependents.contains(n))
+            })
+            .collect();
+
+        DepGraph {
+            nodes: self.nodes.clone(),
+            dependencies,
+            satisfied: self.satisfied.clone(),
+            tags: self.tags.clone(),
+            declared,
+            acyclic: self.acyclic.as_ref().map(TopoOrder::reversed),
+            aliases: self.aliases.clone(),
+            hints: self
+                .hints
+                .iter()
+                .map(|(a, b)| (b.clone(), a.clone()))
+                .collect(),
+        }
+    }
+}
+
+impl<T: Eq + Ord> DepGraph<T> {
+    /// Get every dependency in the graph as a `(node, dependency)` pair, sorted by node
+    /// and then by dependency, so that the result does not depend upon hashing or the
+    /// order in which nodes were registered.
+    pub fn sorted_edges(&self) -> Vec<(&T, &T)> {
+        let mut edges: Vec<(&T, &T)> = self
+            .edge_index_list()
+            .into_iter()
+            .map(|(n, d)| (&self.nodes[n], &self.nodes[d]))
+            .collect();
+        edges.sort_unstable();
+        edges
+    }
+
+    /// Resolve the dependencies of the target node in waves, as `resolve_in_waves` does,
+    /// with the nodes within each wave sorted in ascending order. Target node must exist.
+    pub fn resolve_in_waves_sorted<'a>(
+        &'a self,
+        target: &T,
+    ) -> Result<Vec<Vec<&'a T>>, SolventError> {
+        let mut waves = self.resolve_in_waves(target)?;
+        for wave in waves.iter_mut() {
+            wave.sort();
+        }
+        Ok(waves)
+    }
+}
+
+impl<T: Eq + Debug> DepGraph<T> {
+    /// Resolve the dependencies of the target node, as `resolve` does, but first check
+    /// for phantom nodes (see `phantom_nodes`) amongst every node the target depends
+    /// upon, directly or transitively, including those below satisfied nodes. If there
+    /// are any, `SolventError::PhantomNodes` lists all of them (formatted with `Debug`),
+    /// rather than treating them as nodes without dependencies. Target node must exist.
+    pub fn resolve_strict<'a>(&'a self, target: &T) -> Result<Vec<&'a T>, SolventError> {
+        let pos = self._pos(target).ok_or(SolventError::NoSuchNode)?;
+        let phantoms = self._reachable_phantoms(pos);
+        if !phantoms.is_empty() {
+            return Err(SolventError::PhantomNodes(
+                phantoms
+                    .into_iter()
+                    .map(|n| format!("{:?}", self.nodes[n]))
+                    .collect(),
+            ));
+        }
+        Ok(self
+            ._resolve(pos)?
+            .into_iter()
+            .map(|n| &self.nodes[n])
+            .collect())
+    }
+
+    /// Resolve the dependencies of the target node, as `resolve` does, but first check
+    /// that every satisfied node the target depends upon (directly or transitively) has
+    /// all of its own dependencies satisfied, as `check_satisfied_consistency` does for
+    /// the whole graph. Otherwise `SolventError::DependenciesNotSatisfied` names the first
+    /// such node and dependency (formatted with `Debug`). Target node must exist.
+    pub fn resolve_consistent<'a>(&'a self, target: &T) -> Result<Vec<&'a T>, SolventError> {
+        let pos = self._pos(target).ok_or(SolventError::NoSuchNode)?;
+        let reachable = self._dependencies_closure(pos);
+        if let Some((n, dep)) = self
+            ._satisfied_violations()
+            .into_iter()
+            .find(|(n, _)| reachable.contains(n))
+        {
+            return Err(SolventError::DependenciesNotSatisfied(
+                format!("{:?}", self.nodes[n]),
+                format!("{:?}", self.nodes[dep]),
+            ));
+        }
+        Ok(self
+            ._resolve(pos)?
+            .into_iter()
+            .map(|n| &self.nodes[n])
+            .collect())
+    }
+
+    /// Mark nodes as satisfied, as `mark_as_satisfied` does, but only if every direct
+    /// dependency of each node is already satisfied or is marked earlier in `nodes`.
+    /// Otherwise `SolventError::DependenciesNotSatisfied` names the first such node and
+    /// dependency (formatted with `Debug`), and no nodes are marked. Nodes must exist.
+    pub fn mark_as_satisfied_strict(&mut self, nodes: &[T]) -> Result<(), SolventError> {
+        let mut marking: Vec<usize> = Vec::with_capacity(nodes.len());
+        for node in nodes.iter() {
+            let node_pos = self._pos(node).ok_or(SolventError::NoSuchNode)?;
+            if let Some(dep) = self
+                ._deps_of(node_pos)
+                .find(|dep| !self.satisfied.contains(dep) && !marking.contains(dep))
+            {
+                return Err(SolventError::DependenciesNotSatisfied(
+                    format!("{:?}", self.nodes[node_pos]),
+                    format!("{:?}", self.nodes[dep]),
+                ));
+            }
+            marking.push(node_pos);
+        }
+
+        self.satisfied.extend(marking);
+        Ok(())
+    }
+}
+
+impl<T: Eq + Display> DepGraph<T> {
+    /// Render the graph as a Mermaid flowchart: a `graph TD` block with a
+    /// `node --> dependency` line for each dependency, and a line for each node without
+    /// any. Characters other than ASCII letters, digits and underscores are replaced in
+    /// node ids, ids which are Mermaid keywords or already in use are given a suffix, and
+    /// such nodes are given their original text as a label.
+    pub fn to_mermaid(&self) -> String {
+        // Words with a meaning of their own in a flowchart, which would break it as ids
+        const KEYWORDS: &[&str] = &[
+            "end",
+            "graph",
+            "flowchart",
+            "subgraph",
+            "direction",
+            "style",
+            "linkStyle",
+            "classDef",
+            "class",
+            "click",
+            "call",
+            "href",
+            "default",
+        ];
+
+        let mut ids: Vec<String> = Vec::with_capacity(self.nodes.len());
+        for (pos, node) in self.nodes.iter().enumerate() {
+            let mut id: String = node
+                .to_string()
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                .collect();
+            while id.is_empty() || KEYWORDS.contains(&id.as_str()) || ids.contains(&id) {
+                id = format!("{}_{}", id, pos);
+            }
+            ids.push(id);
+        }
+
+        let mut out = String::from("graph TD\n");
+        for (pos, node) in self.nodes.iter().enumerate() {
+            let text = node.to_string();
+            if text != ids[pos] {
+                let label = text.replace('"', "#quot;");
+                out.push_str(&format!("    {}[\"{}\"]\n", ids[pos], label));
+            }
+        }
+        let edges = self.edge_index_list();
+        for (pos, id) in ids.iter().enumerate() {
+            if !edges.iter().any(|(n, d)| *n == pos || *d == pos) {
+                out.push_str(&format!("    {}\n", id));
+            }
+        }
+        for (n, d) in edges.into_iter() {
+            out.push_str(&format!("    {} --> {}\n", ids[n], ids[d]));
+        }
+        out
+    }
+}
+
+impl<T: Eq + Hash> DepGraph<T> {
+    /// Freeze the graph into an immutable `FrozenDepGraph`, which stores its
+    /// dependencies in flat arrays and indexes its nodes for faster repeated resolution.
+    /// Resolution order matches that of this graph.
+    pub fn freeze(self) -> FrozenDepGraph<T> {
+        let hints = self._hint_positions();
+        let dependencies = (0..self.nodes.len())
+            .map(|pos| self._deps_of(pos).collect())
+            .collect();
+        let satisfied = (0..self.nodes.len())
+            .map(|pos| self.satisfied.contains(&pos))
+            .collect();
+        FrozenDepGraph::new(self.nodes, dependencies, satisfied, self.aliases, hints)
+    }
+
+    /// Compute a 64-bit fingerprint of the structure of the graph (its nodes and
+    /// dependencies, but not which are satisfied). The fingerprint does not depend upon
+    /// the order in which nodes and dependencies were registered, and is stable across
+    /// runs.
+    pub fn structural_fingerprint(&self) -> u64 {
+        let node_hash = |pos: usize| {
+            let mut hasher = FnvHasher::default();
+            self.nodes[pos].hash(&mut hasher);
+            hasher.finish()
+        };
+
+        let mut nodes: Vec<u64> = (0..self.nodes.len()).map(node_hash).collect();
+        nodes.sort_unstable();
+
+        let mut edges: Vec<(u64, u64)> = self
+            .dependencies
+            .iter()
+            .flat_map(|(n, deplist)| deplist.iter().map(move |d| (*n, *d)))
+            .map(|(n, d)| (node_hash(n), node_hash(d)))
+            .collect();
+        edges.sort_unstable();
+
+        // Lengths and hashes are written as fixed-width little-endian bytes, so the
+        // fingerprint is the same whatever the platform's word size and byte order
+        let mut hasher = FnvHasher::default();
+        hasher.write(&(nodes.len() as u64).to_le_bytes());
+        for n in &nodes {
+            hasher.write(&n.to_le_bytes());
+        }
+        hasher.write(&(edges.len() as u64).to_le_bytes());
+        for (n, d) in &edges {
+            hasher.write(&n.to_le_bytes());
+            hasher.write(&d.to_le_bytes());
+        }
+        hasher.finish()
+    }
+}
+
+// A topological order of the nodes of a DepGraph, maintained incrementally as
+// dependencies are added, after Pearce and Kelly's dynamic topological sort.
+#[derive(Debug, Clone, Default)]
+struct TopoOrder {
+    // The rank of each node position. Dependencies rank lower than their dependents.
+    rank: Vec<usize>,
+
+    // The node position at each rank
+    at_rank: Vec<usize>,
+}
+
+impl TopoOrder {
+    fn from_order(order: Vec<usize>) -> TopoOrder {
+        let mut rank = vec![0; order.len()];
+        for (r, pos) in order.iter().enumerate() {
+            rank[*pos] = r;
+        }
+        TopoOrder {
+            rank,
+            at_rank: order,
+        }
+    }
+
+    fn reversed(&self) -> TopoOrder {
+        TopoOrder::from_order(self.at_rank.iter().rev().cloned().collect())
+    }
+
+    fn push(&mut self, pos: usize) {
+        self.rank.push(self.at_rank.len());
+        self.at_rank.push(pos);
+    }
+
+    // Drop the nodes pushed since there were `len`, which must hold the top ranks
+    fn truncate(&mut self, len: usize) {
+        self.rank.truncate(len);
+        self.at_rank.truncate(len);
+    }
+
+    // Update the order for a new dependency of `node` upon `dep`, or if that dependency
+    // would create a cycle leave the order unchanged and return the cycle. Any ranks
+    // changed are recorded in `undo` (see `rollback`).
+    fn add_dependency(
+        &mut self,
+        dependencies: &HashMap<usize, DepSet>,
+        node: usize,
+        dep: usize,
+        undo: &mut Vec<(usize, Vec<usize>)>,
+    ) -> Result<(), Vec<usize>> {
+        if node == dep {
+            return Err(vec![node, dep]);
+        }
+        let lower = self.rank[node];
+        let upper = self.rank[dep];
+        if upper < lower {
+            return Ok(());
+        }
+
+        // Only the dependencies of `dep` ranked above `node` could lead back to `node`,
+        // and they are exactly the nodes which must move below it.
+        let mut parent: HashMap<usize, usize> = HashMap::new();
+        let mut found = vec![dep];
+        let mut stack = vec![dep];
+        while let Some(n) = stack.pop() {
+            for d in dependencies.get(&n).into_iter().flatten() {
+                if *d == node {
+                    let mut cycle = vec![node, n];
+                    let mut p = n;
+                    while let Some(pp) = parent.get(&p) {
+                        cycle.push(*pp);
+                        p = *pp;
+                    }
+                    cycle[1..].reverse();
+                    cycle.push(node);
+                    return Err(cycle);
+                }
+                if self.rank[*d] > lower && *d != dep && !parent.contains_key(d) {
+                    parent.insert(*d, n);
+                    found.push(*d);
+                    stack.push(*d);
+                }
+            }
+        }
+
+        // Re-rank the nodes between `node` and `dep`, putting those found first. Nodes
+        // keep their relative order within each group, so no dependency is violated.
+        found.sort_by_key(|n| self.rank[*n]);
+        undo.push((lower, self.at_rank[lower..=upper].to_vec()));
+        let rest: Vec<usize> = (lower..=upper)
+            .map(|r| self.at_rank[r])
+            .filter(|n| *n != dep && !parent.contains_key(n))
+            .collect();
+        for (i, n) in found.into_iter().chain(rest).enumerate() {
+            self.rank[n] = lower + i;
+            self.at_rank[lower + i] = n;
+        }
+        Ok(())
+    }
+
+    // Undo the re-ranking recorded by `add_dependency`, restoring each changed range of
+    // ranks (given by its lowest rank and the nodes it held) in reverse order.
+    fn rollback(&mut self, undo: Vec<(usize, Vec<usize>)>) {
+        for (lower, nodes) in undo.into_iter().rev() {
+            for (i, n) in nodes.into_iter().enumerate() {
+                self.rank[n] = lower + i;
+                self.at_rank[lower + i] = n;
+            }
+        }
+    }
+}
+
+// A 64-bit FNV-1a hasher. Unlike the std hashers it is not randomly seeded, so its
+// output is stable across runs.
+struct FnvHasher(u64);
+
+impl Default for FnvHasher {
+    fn default() -> Self {
+        FnvHasher(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for FnvHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes.iter() {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Resolves the dependencies of a sequence of targets, returned by
+/// `DepGraph::resolver()`. Nodes output when resolving one target are treated as
+/// satisfied when resolving later targets.
+pub struct Resolver<'a, T: Eq + 'a> {
+    depgraph: &'a DepGraph<T>,
+
+    // Node positions output by previous calls to resolve
+    emitted: HashSet<usize>,
+}
+
+impl<'a, T: Eq> Resolver<'a, T> {
+    /// Resolve the dependencies of the target node, as `DepGraph::resolve` does, skipping
+    /// any nodes already output by this Resolver. Target node must exist.
+    pub fn resolve(&mut self, target: &T) -> Result<Vec<&'a T>, SolventError> {
+        let depgraph = self.depgraph;
+        let pos = depgraph._pos(target).ok_or(SolventError::NoSuchNode)?;
+
+        let skip = |n| depgraph.satisfied.contains(&n);
+        let mut visited = self.emitted.clone();
+        let mut order = Vec::new();
+        depgraph._visit(pos, &skip, &mut visited, &mut order)?;
+
+        self.emitted = visited;
+        Ok(order.into_iter().map(|n| &depgraph.nodes[n]).collect())
+    }
+}
+
+/// The result of `DepGraph::resolve_status()`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResolveStatus<'a, T> {
+    /// The target is already satisfied, so there is nothing to do
+    AlreadySatisfied,
+    /// The nodes which must be processed, in order, to satisfy the target
+    Pending(Vec<&'a T>),
+}
+
+/// Whether a node reported by `DepGraph::resolve_annotated()` still needs processing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeStatus {
+    /// The node is not satisfied, and would be output by `resolve`
+    Pending,
+    /// The node is satisfied, or is only needed through satisfied nodes, and would be
+    /// skipped by `resolve`
+    Satisfied,
+}
+
+/// Counts of the work done by `DepGraph::resolve_with_metrics()`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ResolveMetrics {
+    /// The number of times a node was reached, including nodes reached again by another
+    /// path and satisfied nodes
+    pub visits: usize,
+    /// The number of times a satisfied node was reached, and so not descended into
+    pub prunes: usize,
+    /// The length of the longest chain of nodes descended through
+    pub max_depth: usize,
+}
+
+/// A snapshot of which nodes of a DepGraph are satisfied, returned by
+/// `DepGraph::snapshot_satisfied()`.
+#[derive(Debug, Clone)]
+pub struct SatisfiedSnapshot {
+    satisfied: BitSet,
+}
+
+/// A handle to a node in a DepGraph, returned by `DepGraph::node()`.
+pub struct NodeHandle<'a, T: Eq + 'a> {
+    depgraph: &'a mut DepGraph<T>,
+
+    // Position of the node in the nodes array
+    pos: usize,
+}
+
+impl<'a, T: Eq> NodeHandle<'a, T> {
+    /// Add a dependency to this node. The dependency node does not need to pre-exist.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the graph was created with `DepGraph::new_acyclic` and the dependency
+    /// would create a cycle. Use `try_depends_on` to handle this as an error.
+    pub fn depends_on(self, dep: T) -> Self {
+        match self.try_depends_on(dep) {
+            Ok(handle) => handle,
+            Err(e) => panic!("Dependency would create a cycle: {}", e),
+        }
+    }
+
+    /// Add a dependency to this node, as `depends_on` does, unless the graph was created
+    /// with `DepGraph::new_acyclic` and the dependency would create a cycle, in which case
+    /// `SolventError::CycleDetected` is returned and the graph is left unchanged.
+    pub fn try_depends_on(self, dep: T) -> Result<Self, SolventError> {
+        if let Some(dep_pos) = self.depgraph._pos(&dep) {
+            self.depgraph
+                ._order_dependencies(&[(self.pos, dep_pos)], &mut Vec::new())?;
+        }
+        let dep_pos = self.depgraph._register_node(dep);
+        self.depgraph._register_dependency(self.pos, dep_pos);
+        Ok(self)
+    }
+}
+
+/// This iterates through the dependencies of the DepGraph's target. Progress is kept
+/// within the iterator, so it can be drained in batches (e.g. with `by_ref().take(n)`)
+/// and later batches carry on where earlier ones stopped.
+pub struct DepGraphIterator<'a, T: Eq + 'a> {
+    depgraph: &'a DepGraph<T>,
+
+    // Target we are trying to satisfy
+    target: usize,
+
+    // Node positions already satisfied during this iterator's walk
+    satisfied: BitSet,
+
+    // Current path, for cycle detection
+    curpath: Vec<usize>,
+
+    // Halted.  Used so that it can return None after an Err is returned.
+    halted: bool,
+
+    // If set, the order in which dependencies are visited is shuffled by this seed
+    seed: Option<u64>,
+
+    // Whether the hints have been checked for any which apply to the target's dependencies
+    hints_checked: bool,
+
+    // If any hints apply, the rest of the order once they have been applied
+    hinted: Option<VecDeque<usize>>,
+
+    // An error hit while resolving the whole order to apply hints, to be returned after
+    // the nodes resolved before it
+    error: Option<SolventError>,
+}
+
+impl<'a, T: Eq> DepGraphIterator<'a, T> {
+    // Get the position of the next dependency, without applying hints
+    fn next_pos(&mut self) -> Option<Result<usize, SolventError>> {
+        if self.halted {
+            return None;
+        }
+
+        let npos = self.target;
+        if self.satisfied.contains(&npos) {
+            self.halted = true;
+            return None;
+        }
+
+        self.curpath.drain(..);
+        let next = match self.get_next_dependency(npos) {
+            Ok(d) => d,
+            Err(e) => {
+                self.halted = true;
+                return Some(Err(e));
+            }
+        };
+        self.satisfied.insert(next);
+        Some(Ok(next))
+    }
+
+    fn get_next_dependency(&mut self, pos: usize) -> Result<usize, SolventError> {
+        next_dependency(
+            &self.depgraph.dependencies,
+            &self.satisfied,
+            &mut self.curpath,
+            self.seed,
+            pos,
+        )
+    }
+}
+
+// Descend from `pos` through its unsatisfied dependencies (chosen by `seed`, if set) to a
+// node whose dependencies are all satisfied, and return it. `curpath` collects the nodes
+// descended through, to detect cycles.
+fn next_dependency(
+    dependencies: &HashMap<usize, DepSet>,
+    satisfied: &BitSet,
+    curpath: &mut Vec<usize>,
+    seed: Option<u64>,
+    pos: usize,
+) -> Result<usize, SolventError> {
+    if curpath.contains(&pos) {
+        let s = format!("{:?}", curpath);
+        return Err(SolventError::CycleDetected(s));
+    }
+    curpath.push(pos);
+
+    let deplist = match dependencies.get(&pos) {
+        None => return Ok(pos),
+        Some(deplist) => deplist,
+    };
+
+    // Prune satisfied nodes
+    let mut unsatisfied = deplist.iter().filter(|n| !satisfied.contains(n));
+    let next = match seed {
+        None => unsatisfied.next(),
+        Some(seed) => unsatisfied.min_by_key(|n| (shuffle_key(seed, pos, **n), **n)),
+    };
+    match next {
+        Some(n) => next_dependency(dependencies, satisfied, curpath, seed, *n),
+        // nodes dependencies are satisfied
+        None => Ok(pos),
+    }
+}
+
+// Reorder `order`, a resolution order, so that for each hint `(before, after)` with both
+// nodes present, `before` comes first unless dependencies prevent it. An order which no
+// hint applies to is returned unchanged.
+fn apply_hints(
+    order: Vec<usize>,
+    hints: &[(usize, usize)],
+    deps_of: &dyn Fn(usize) -> Vec<usize>,
+) -> Vec<usize> {
+    // The index of each node within the order
+    let index: HashMap<usize, usize> = order.iter().enumerate().map(|(i, n)| (*n, i)).collect();
+
+    // Skip hints which are absent from the order, or which `before` depending upon
+    // `after` makes impossible to follow
+    let depends_upon = |from: usize, to: usize| {
+        let mut stack = vec![from];
+        let mut seen = HashSet::new();
+        while let Some(n) = stack.pop() {
+            if n == to {
+                return true;
+            }
+            if seen.insert(n) {
+                stack.extend(deps_of(n));
+            }
+        }
+        false
+    };
+    let hints: Vec<(usize, usize)> = hints
+        .iter()
+        .cloned()
+        .filter(|(before, after)| index.contains_key(before) && index.contains_key(after))
+        .filter(|(before, after)| !depends_upon(*before, *after))
+        .collect();
+    if hints.is_empty() {
+        return order;
+    }
+
+    // Count, for each node, the dependencies and hinted predecessors not yet output
+    let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); order.len()];
+    let mut waiting_deps = vec![0; order.len()];
+    for (i, n) in order.iter().enumerate() {
+        for dep in deps_of(*n) {
+            if let Some(d) = index.get(&dep) {
+                dependents[*d].push(i);
+                waiting_deps[i] += 1;
+            }
+        }
+    }
+    let mut followers: Vec<Vec<usize>> = vec![Vec::new(); order.len()];
+    let mut waiting_hints = vec![0; order.len()];
+    for (before, after) in hints.iter() {
+        let after = index[after];
+        followers[index[before]].push(after);
+        waiting_hints[after] += 1;
+    }
+
+    // Output ready nodes in their original order, preferring those no hint holds back
+    let mut unhinted = BTreeSet::new();
+    let mut held = BTreeSet::new();
+    for i in 0..order.len() {
+        if waiting_deps[i] == 0 {
+            if waiting_hints[i] == 0 {
+                unhinted.insert(i);
+            } else {
+                held.insert(i);
+            }
+        }
+    }
+    fn pop_first(set: &mut BTreeSet<usize>) -> Option<usize> {
+        let first = *set.iter().next()?;
+        set.remove(&first);
+        Some(first)
+    }
+    let mut emitted = vec![false; order.len()];
+    let mut hinted = Vec::with_capacity(order.len());
+    while let Some(i) = pop_first(&mut unhinted).or_else(|| pop_first(&mut held)) {
+        emitted[i] = true;
+        hinted.push(order[i]);
+        for d in dependents[i].iter() {
+            waiting_deps[*d] -= 1;
+            if waiting_deps[*d] == 0 {
+                if waiting_hints[*d] == 0 {
+                    unhinted.insert(*d);
+                } else {
+                    held.insert(*d);
+                }
+            }
+        }
+        for a in followers[i].iter() {
+            if !emitted[*a] {
+                waiting_hints[*a] -= 1;
+                if waiting_hints[*a] == 0 && held.remove(a) {
+                    unhinted.insert(*a);
+                }
+            }
+        }
+    }
+    hinted
+}
+
+// The position of the dependency `dep` of `pos` amongst its siblings, when shuffled by
+// `seed`
+fn shuffle_key(seed: u64, pos: usize, dep: usize) -> u64 {
+    let mut hasher = FnvHasher::default();
+    seed.hash(&mut hasher);
+    pos.hash(&mut hasher);
+    dep.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl<'a, T: Eq> Iterator for DepGraphIterator<'a, T> {
+    type Item = Result<&'a T, SolventError>;
+
+    // Get next dependency.  Returns None when finished.  If Some(Err(SolventError)) occurs,
+    // all subsequent calls will return None.
+    fn next(&mut self) -> Option<Result<&'a T, SolventError>> {
+        let depgraph = self.depgraph;
+        if !self.hints_checked {
+            self.hints_checked = true;
+            if !depgraph.hints.is_empty() {
+                let reachable: Vec<usize> = depgraph
+                    ._dependencies_closure(self.target)
+                    .into_iter()
+                    .collect();
+                if !depgraph._hints_within(&reachable).is_empty() {
+                    // Hints can only be applied once the whole order is known
+                    let mut order = Vec::new();
+                    while let Some(next) = self.next_pos() {
+                        match next {
+                            Ok(n) => order.push(n),
+                            Err(e) => {
+                                self.error = Some(e);
+                                break;
+                            }
+                        }
+                    }
+                    self.hinted = Some(depgraph._apply_hints(order).into());
+                }
+            }
+        }
+
+        let next = match self.hinted.as_mut() {
+            Some(hinted) => match hinted.pop_front() {
+                Some(n) => Some(Ok(n)),
+                None => self.error.take().map(Err),
+            },
+            None => self.next_pos(),
+        };
+        next.map(|next| next.map(|n| &depgraph.nodes[n]))
+    }
+}
+
+// Moves the unsatisfied nodes out of a DepGraph in topological order, for
+// `DepGraph::into_iter_topological()`. The dependencies of each node in turn are walked
+// as `DepGraphIterator` walks those of its target.
+struct IntoIterTopological<T> {
+    nodes: Vec<Option<T>>,
+    dependencies: HashMap<usize, DepSet>,
+
+    // Node positions satisfied, either beforehand or by having been output
+    satisfied: BitSet,
+
+    // Current path, for cycle detection
+    curpath: Vec<usize>,
+
+    // Position of the node whose dependencies are being output
+    target: usize,
+
+    // Halted.  Used so that it can return None after an Err is returned.
+    halted: bool,
+}
+
+impl<T> Iterator for IntoIterTopological<T> {
+    type Item = Result<T, SolventError>;
+
+    fn next(&mut self) -> Option<Result<T, SolventError>> {
+        if self.halted {
+            return None;
+        }
+        while self.satisfied.contains(&self.target) {
+            self.target += 1;
+        }
+        if self.target >= self.nodes.len() {
+            self.halted = true;
+            return None;
+        }
+
+        self.curpath.clear();
+        match next_dependency(
+            &self.dependencies,
+            &self.satisfied,
+            &mut self.curpath,
+            None,
+            self.target,
+        ) {
+            Ok(pos) => {
+                self.satisfied.insert(pos);
+                Some(Ok(self.nodes[pos]
+                    .take()
+                    .expect("each node is output once")))
+            }
+            Err(e) => {
+                self.halted = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{BitSet, DepSet};
+    use super::{DepGraph, NodeStatus, ResolveStatus};
+    use super::{ResolveOrUser, SolventError};
+    use alloc::format;
+    use alloc::string::{String, ToString};
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    // The graph used by solvent_test_branching
+    fn branching_graph() -> DepGraph<&'static str> {
+        let mut depgraph: DepGraph<&str> = DepGraph::new();
+
+        depgraph.register_nodes(vec![
+            "a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l", "m", "n",
+        ]);
+
+        depgraph.register_dependencies("a", vec!["b", "c", "d"]);
+        depgraph.register_dependency("b", "d");
+        depgraph.register_dependencies("c", vec!["e", "m", "g"]);
+        depgraph.register_dependency("e", "f");
+        depgraph.register_dependency("g", "h");
+        depgraph.register_dependency("h", "i");
+        depgraph.register_dependencies("i", vec!["j", "k"]);
+        depgraph.register_dependencies("k", vec!["l", "m"]);
+        depgraph.register_dependency("m", "n");
+
+        depgraph
+    }
+
+    #[test]
+    fn solvent_test_branching() {
+        let mut depgraph: DepGraph<&str> = DepGraph::new();
+
+        depgraph.register_nodes(vec![
+            "a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l", "m", "n",
+        ]);
+
+        depgraph.register_dependencies("a", vec!["b", "c", "d"]);
+        depgraph.register_dependency("b", "d");
+        depgraph.register_dependencies("c", vec!["e", "m", "g"]);
+        depgraph.register_dependency("e", "f");
+        depgraph.register_dependency("g", "h");
+        depgraph.register_dependency("h", "i");
+        depgraph.register_dependencies("i", vec!["j", "k"]);
+        depgraph.register_dependencies("k", vec!["l", "m"]);
+        depgraph.register_dependency("m", "n");
+
+        let mut results = Vec::new();
+
+        for node in depgraph.dependencies_of(&"a").unwrap() {
+            // detect infinite looping bugs
+            assert!(results.len() < 30);
+
+            let n = match node {
+                Err(e) => panic!("Solvent error detected: {:?}", e),
+                Ok(n) => n,
+            };
+
+            // Check that all of that nodes dependencies have already been output
+            let pos = depgraph._pos(n).unwrap();
+            let deps: Option<&DepSet> = depgraph.dependencies.get(&pos);
+            if let Some(deps) = deps {
+                for dep in deps.iter() {
+                    assert!(results.contains(&depgraph.nodes[*dep]));
+                }
+            }
+
+            results.push(*n);
+        }
+
+        // Be sure we actually output enough stuff
+        assert!(results.len() == 14);
+
+        // Be sure each output is unique
+        for result in results.iter() {
+            let mut count: usize = 0;
+            for result2 in results.iter() {
+                if result == result2 {
+                    count += 1;
+                }
+            }
+            assert!(count == 1);
+        }
+    }
+
+    #[test]
+    fn solvent_test_updating_dependencies() {
+        let mut depgraph: DepGraph<&str> = DepGraph::new();
+
+        depgraph.register_dependencies("a", vec!["b", "c"]);
+        depgraph.register_dependency("a", "d");
+        assert!(depgraph.dependencies.get(&0).unwrap().contains(&1));
+        assert!(depgraph.dependencies.get(&0).unwrap().contains(&2));
+        assert!(depgraph.dependencies.get(&0).unwrap().contains(&3));
+    }
+
+    #[test]
+    fn solvent_test_circular() {
+        let mut depgraph: DepGraph<&str> = DepGraph::new();
+        depgraph.register_dependency("a", "b");
+        depgraph.register_dependency("b", "c");
+        depgraph.register_dependency("c", "a");
+
+        for node in depgraph.dependencies_of(&"a").unwrap() {
+            assert!(node.is_err());
+        }
+    }
+
+    // The graph used by solvent_test_satisfied_stoppage, without any satisfied nodes
+    fn stoppage_graph() -> DepGraph<&'static str> {
+        let mut depgraph: DepGraph<&str> = DepGraph::new();
+        depgraph.register_dependencies("superconn", vec![]);
+        depgraph.register_dependencies("owneruser", vec!["superconn"]);
+        depgraph.register_dependencies("appuser", vec!["superconn"]);
+        depgraph.register_dependencies("database", vec!["owneruser"]);
+        depgraph.register_dependencies("ownerconn", vec!["database", "owneruser"]);
+        depgraph.register_dependencies("adminconn", vec!["database"]);
+        depgraph.register_dependencies("extensions", vec!["database", "adminconn"]);
+        depgraph.register_dependencies("schema_table", vec!["database", "ownerconn"]);
+        depgraph.register_dependencies(
+            "schemas",
+            vec!["ownerconn", "extensions", "schema_table", "appuser"],
+        );
+        depgraph.register_dependencies("appconn", vec!["database", "appuser", "schemas"]);
+        depgraph
+    }
+
+    #[test]
+    fn solvent_test_satisfied_stoppage() {
+        let mut depgraph: DepGraph<&str> = DepGraph::new();
+        depgraph.register_dependencies("superconn", vec![]);
+        depgraph.register_dependencies("owneruser", vec!["superconn"]);
+        depgraph.register_dependencies("appuser", vec!["superconn"]);
+        depgraph.register_dependencies("database", vec!["owneruser"]);
+        depgraph.register_dependencies("ownerconn", vec!["database", "owneruser"]);
+        depgraph.register_dependencies("adminconn", vec!["database"]);
+        depgraph.register_dependencies("extensions", vec!["database", "adminconn"]);
+        depgraph.register_dependencies("schema_table", vec!["database", "ownerconn"]);
+        depgraph.register_dependencies(
+            "schemas",
+            vec!["ownerconn", "extensions", "schema_table", "appuser"],
+        );
+        depgraph.register_dependencies("appconn", vec!["database", "appuser", "schemas"]);
+
+        depgraph
+            .mark_as_satisfied(&["owneruser", "appuser"])
+            .unwrap();
+        assert_eq!(depgraph.satisfied.len(), 2);
+
+        let mut results: Vec<&str> = Vec::new();
+
+        for node in depgraph.dependencies_of(&"appconn").unwrap() {
+            assert!(results.len() < 30);
+            match node {
+                Ok(n) => results.push(n),
+                Err(e) => panic!("Solvent error detected: {:?}", e),
+            };
+        }
+
+        // Be sure we did not depend on these
+        assert!(!results.contains(&"appuser"));
+        assert!(!results.contains(&"owneruser"));
+        assert!(!results.contains(&"superconn"));
+
+        // Be sure we actually output enough stuff
+        assert!(results.len() == 7);
+
+        // Be sure each output is unique
+        for result in results.iter() {
+            let mut count: usize = 0;
+            for result2 in results.iter() {
+                if result == result2 {
+                    count += 1;
+                }
+            }
+            assert!(count == 1);
+        }
+    }
+
+    #[test]
+    fn solvent_test_core_resolution() {
+        // Exercises the core API only, so it runs under every feature configuration
+        // including `--no-default-features --features alloc`.
+        let mut depgraph: DepGraph<&str> = DepGraph::new();
+        depgraph.register_dependencies("a", vec!["b", "c"]);
+        depgraph.register_dependency("b", "c");
+        depgraph.mark_as_satisfied(&["c"]).unwrap();
+
+        let results: Vec<&str> = depgraph
+            .dependencies_of(&"a")
+            .unwrap()
+            .map(|n| *n.unwrap())
+            .collect();
+        assert_eq!(results, vec!["b", "a"]);
+
+        assert_eq!(
+            depgraph.dependencies_of(&"z").err(),
+            Some(SolventError::NoSuchNode)
+        );
+    }
+
+    #[test]
+    fn solvent_test_node_handle() {
+        let mut direct: DepGraph<&str> = DepGraph::new();
+        direct.register_dependency("a", "b");
+        direct.register_dependency("a", "c");
+        direct.register_dependency("b", "c");
+
+        let mut fluent: DepGraph<&str> = DepGraph::new();
+        fluent.node("a").depends_on("b").depends_on("c");
+        fluent.node("b").depends_on("c");
+
+        assert_eq!(direct.nodes, fluent.nodes);
+        assert_eq!(direct.dependencies, fluent.dependencies);
+    }
+
+    #[test]
+    fn solvent_test_dependencies_within() {
+        let depgraph = branching_graph();
+
+        let results = depgraph.dependencies_within(&"a", 1).unwrap();
+        assert_eq!(results.len(), 4);
+        assert_eq!(*results[3], "a");
+        for n in ["b", "c", "d"].iter() {
+            assert!(results.contains(&n));
+        }
+        // b depends on d, so d must precede it
+        let b = results.iter().position(|n| **n == "b").unwrap();
+        let d = results.iter().position(|n| **n == "d").unwrap();
+        assert!(d < b);
+
+        assert_eq!(depgraph.dependencies_within(&"a", 0).unwrap(), vec![&"a"]);
+        assert_eq!(depgraph.dependencies_within(&"a", 2).unwrap().len(), 7);
+    }
+
+    #[test]
+    fn solvent_test_structural_fingerprint() {
+        let mut forward: DepGraph<&str> = DepGraph::new();
+        forward.register_dependencies("a", vec!["b", "c"]);
+        forward.register_dependency("b", "c");
+
+        let mut backward: DepGraph<&str> = DepGraph::new();
+        backward.register_node("c");
+        backward.register_dependency("b", "c");
+        backward.register_dependency("a", "c");
+        backward.register_dependency("a", "b");
+
+        assert_eq!(
+            forward.structural_fingerprint(),
+            backward.structural_fingerprint()
+        );
+
+        let mut different: DepGraph<&str> = DepGraph::new();
+        different.register_dependencies("a", vec!["b", "c"]);
+        different.register_dependency("c", "b");
+
+        assert_ne!(
+            forward.structural_fingerprint(),
+            different.structural_fingerprint()
+        );
+    }
+
+    #[test]
+    fn solvent_test_register_dependencies_reporting() {
+        let mut depgraph: DepGraph<&str> = DepGraph::new();
+        assert_eq!(
+            depgraph.register_dependencies_reporting("a", vec!["b", "b", "c"]),
+            1
+        );
+        assert_eq!(depgraph.dependencies.get(&0).unwrap().len(), 2);
+
+        assert_eq!(
+            depgraph.register_dependencies_reporting("a", vec!["c", "d"]),
+            1
+        );
+        assert_eq!(depgraph.dependencies.get(&0).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn solvent_test_into_topological_order() {
+        let depgraph = branching_graph();
+        let expected = depgraph.clone();
+
+        let order: Vec<&str> = depgraph.into_topological_order().unwrap();
+        assert_eq!(order.len(), 14);
+        for (i, node) in order.iter().enumerate() {
+            let pos = expected._pos(node).unwrap();
+            if let Some(deps) = expected.dependencies.get(&pos) {
+                for dep in deps.iter() {
+                    assert!(order[..i].contains(&expected.nodes[*dep]));
+                }
+            }
+        }
+
+        let mut cyclic: DepGraph<&str> = DepGraph::new();
+        cyclic.register_dependency("a", "b");
+        cyclic.register_dependency("b", "a");
+        assert!(matches!(
+            cyclic.into_topological_order(),
+            Err(SolventError::CycleDetected(_))
+        ));
+    }
+
+    #[test]
+    fn solvent_test_resolve_excluding_tags() {
+        let mut depgraph = stoppage_graph();
+        depgraph
+            .tag_node(&"superconn", "external".to_string())
+            .unwrap();
+        depgraph
+            .tag_node(&"appuser", "optional".to_string())
+            .unwrap();
+        assert_eq!(
+            depgraph.tag_node(&"nope", "optional".to_string()),
+            Err(SolventError::NoSuchNode)
+        );
+
+        let results = depgraph
+            .resolve_excluding_tags(&"appconn", &["external"])
+            .unwrap();
+        assert!(!results.contains(&&"superconn"));
+        assert!(results.contains(&&"owneruser"));
+        assert!(results.contains(&&"appuser"));
+        assert_eq!(results.len(), 9);
+        assert_eq!(*results[8], "appconn");
+    }
+
+    #[test]
+    fn solvent_test_is_valid_order() {
+        let mut depgraph = stoppage_graph();
+        depgraph
+            .mark_as_satisfied(&["owneruser", "appuser"])
+            .unwrap();
+
+        let good = [
+            "database",
+            "ownerconn",
+            "adminconn",
+            "extensions",
+            "schema_table",
+            "schemas",
+            "appconn",
+        ];
+        assert!(depgraph.is_valid_order(&"appconn", &good).unwrap());
+
+        // schemas before its dependency schema_table
+        let bad = [
+            "database",
+            "ownerconn",
+            "adminconn",
+            "extensions",
+            "schemas",
+            "schema_table",
+            "appconn",
+        ];
+        assert!(!depgraph.is_valid_order(&"appconn", &bad).unwrap());
+
+        // includes a satisfied node
+        let extra = ["owneruser", "database", "adminconn"];
+        assert!(!depgraph.is_valid_order(&"adminconn", &extra).unwrap());
+        assert!(depgraph
+            .is_valid_order(&"adminconn", &["database", "adminconn"])
+            .unwrap());
+    }
+
+    #[test]
+    fn solvent_test_copy_satisfied_from() {
+        let mut first: DepGraph<&str> = DepGraph::new();
+        first.register_dependencies("x", vec!["b", "y"]);
+        first.mark_as_satisfied(&["b", "y"]).unwrap();
+
+        let mut second: DepGraph<&str> = DepGraph::new();
+        second.register_dependencies("a", vec!["b", "c"]);
+        second.copy_satisfied_from(&first);
+        assert_eq!(second.satisfied.len(), 1);
+
+        let results: Vec<&str> = second
+            .dependencies_of(&"a")
+            .unwrap()
+            .map(|n| *n.unwrap())
+            .collect();
+        assert_eq!(results, vec!["c", "a"]);
+    }
+
+    #[test]
+    fn solvent_test_reverse_topological_sort() {
+        let depgraph = branching_graph();
+
+        let order = depgraph.reverse_topological_sort().unwrap();
+        assert_eq!(order.len(), 14);
+        assert_eq!(*order[0], "a");
+        let last = depgraph._pos(order[13]).unwrap();
+        assert!(!depgraph.dependencies.contains_key(&last));
+
+        // Every node precedes its dependencies
+        for (i, node) in order.iter().enumerate() {
+            let pos = depgraph._pos(node).unwrap();
+            if let Some(deps) = depgraph.dependencies.get(&pos) {
+                for dep in deps.iter() {
+                    assert!(order[i + 1..].contains(&&depgraph.nodes[*dep]));
+                }
+            }
+        }
+
+        let mut cyclic: DepGraph<&str> = DepGraph::new();
+        cyclic.register_dependency("a", "b");
+        cyclic.register_dependency("b", "a");
+        assert!(cyclic.reverse_topological_sort().is_err());
+    }
+
+    #[test]
+    fn solvent_test_critical_nodes() {
+        // A diamond (a, b, c, d) with a stem (d, e, f) hanging off the bottom
+        let mut depgraph: DepGraph<&str> = DepGraph::new();
+        depgraph.register_dependencies("a", vec!["b", "c"]);
+        depgraph.register_dependency("b", "d");
+        depgraph.register_dependency("c", "d");
+        depgraph.register_dependency("d", "e");
+        depgraph.register_dependency("e", "f");
+
+        let critical = depgraph.critical_nodes(&"a").unwrap();
+        assert_eq!(critical, vec![&"e", &"d"]);
+
+        depgraph.register_dependency("c", "f");
+        let critical = depgraph.critical_nodes(&"a").unwrap();
+        assert_eq!(critical, vec![&"d"]);
+
+        depgraph.register_dependency("f", "a");
+        assert!(depgraph.critical_nodes(&"a").is_err());
+    }
+
+    #[test]
+    fn solvent_test_resolve_chunks() {
+        let depgraph = branching_graph();
+
+        let waves = depgraph.resolve_in_waves(&"a").unwrap();
+        assert_eq!(waves.last().unwrap(), &vec![&"a"]);
+        assert_eq!(waves.iter().map(|w| w.len()).sum::<usize>(), 14);
+
+        let chunks = depgraph.resolve_chunks(&"a", 2).unwrap();
+        assert!(chunks.len() > waves.len());
+        let mut emitted: Vec<&str> = Vec::new();
+        for chunk in chunks.iter() {
+            assert!(!chunk.is_empty() && chunk.len() <= 2);
+            // Every dependency was emitted in an earlier chunk
+            for node in chunk.iter() {
+                let pos = depgraph._pos(node).unwrap();
+                if let Some(deps) = depgraph.dependencies.get(&pos) {
+                    for dep in deps.iter() {
+                        assert!(emitted.contains(&depgraph.nodes[*dep]));
+                    }
+                }
+            }
+            emitted.extend(chunk.iter().cloned());
+        }
+        assert_eq!(emitted.len(), 14);
+
+        let singles = depgraph.resolve_chunks(&"a", 0).unwrap();
+        assert_eq!(singles, depgraph.resolve_chunks(&"a", 1).unwrap());
+        assert_eq!(singles.len(), 14);
+    }
+
+    #[test]
+    fn solvent_test_merge_nodes() {
+        let mut depgraph: DepGraph<&str> = DepGraph::new();
+        depgraph.register_dependencies("x", vec!["a ", "z"]);
+        depgraph.register_dependency("y", "a ");
+        depgraph.register_dependency("a ", "w");
+        depgraph.register_dependency("a", "v");
+        depgraph.mark_as_satisfied(&["a "]).unwrap();
+
+        assert_eq!(
+            depgraph.merge_nodes(&"a", &"nope"),
+            Err(SolventError::NoSuchNode)
+        );
+        depgraph.merge_nodes(&"a", &"a ").unwrap();
+
+        assert!(depgraph._pos(&"a ").is_none());
+        assert_eq!(depgraph.nodes.len(), 6);
+        let a = depgraph._pos(&"a").unwrap();
+        let x = depgraph._pos(&"x").unwrap();
+        let y = depgraph._pos(&"y").unwrap();
+        let w = depgraph._pos(&"w").unwrap();
+        let v = depgraph._pos(&"v").unwrap();
+        assert!(depgraph.dependencies.get(&x).unwrap().contains(&a));
+        assert!(depgraph.dependencies.get(&y).unwrap().contains(&a));
+        assert!(depgraph.dependencies.get(&a).unwrap().contains(&w));
+        assert!(depgraph.dependencies.get(&a).unwrap().contains(&v));
+        assert!(depgraph.satisfied.contains(&a));
+        assert_eq!(depgraph.satisfied.len(), 1);
+    }
+
+    #[test]
+    fn solvent_test_for_each_dependency() {
+        let depgraph = branching_graph();
+
+        let mut seen: Vec<&str> = Vec::new();
+        depgraph
+            .for_each_dependency(&"a", |n| {
+                seen.push(*n);
+                Ok::<(), ()>(())
+            })
+            .unwrap();
+        assert_eq!(seen.len(), 14);
+        assert_eq!(seen[13], "a");
+
+        let mut count = 0;
+        let result = depgraph.for_each_dependency(&"a", |_| {
+            count += 1;
+            if count == 3 {
+                Err("stop")
+            } else {
+                Ok(())
+            }
+        });
+        assert_eq!(result, Err(ResolveOrUser::User("stop")));
+        assert_eq!(count, 3);
+
+        let result = depgraph.for_each_dependency(&"z", |_| Ok::<(), ()>(()));
+        assert_eq!(
+            result,
+            Err(ResolveOrUser::Resolve(SolventError::NoSuchNode))
+        );
+    }
+
+    #[test]
+    fn solvent_test_redundant_edges() {
+        let mut depgraph: DepGraph<&str> = DepGraph::new();
+        depgraph.register_dependency("a", "b");
+        depgraph.register_dependency("b", "c");
+        depgraph.register_dependency("a", "c");
+        assert_eq!(depgraph.redundant_edges().unwrap(), vec![(&"a", &"c")]);
+
+        // Redundant through a longer path
+        depgraph.register_dependency("c", "d");
+        depgraph.register_dependency("a", "d");
+        let redundant = depgraph.redundant_edges().unwrap();
+        assert_eq!(redundant.len(), 2);
+        assert!(redundant.contains(&(&"a", &"d")));
+        assert_eq!(depgraph.nodes.len(), 4);
+
+        assert!(branching_graph()
+            .redundant_edges()
+            .unwrap()
+            .contains(&(&"a", &"d")));
+
+        depgraph.register_dependency("d", "a");
+        assert!(depgraph.redundant_edges().is_err());
+    }
+
+    #[test]
+    fn solvent_test_connected_components() {
+        let mut depgraph: DepGraph<&str> = DepGraph::new();
+        depgraph.register_dependencies("a", vec!["b", "c"]);
+        depgraph.register_dependency("x", "y");
+        depgraph.register_dependency("c", "d");
+        depgraph.register_dependency("z", "y");
+
+        let components = depgraph.connected_components();
+        assert_eq!(components.len(), 2);
+        assert_eq!(components[0], vec![&"a", &"b", &"c", &"d"]);
+        assert_eq!(components[1], vec![&"x", &"y", &"z"]);
+
+        let resolved = depgraph.resolve_components().unwrap();
+        assert_eq!(resolved.len(), 2);
+        assert_eq!(resolved[0].len(), 4);
+        assert_eq!(*resolved[0][3], "a");
+        assert_eq!(resolved[1], vec![&"y", &"x", &"z"]);
+    }
+
+    #[test]
+    fn solvent_test_register_dependencies_arr() {
+        let mut from_vec: DepGraph<&str> = DepGraph::new();
+        from_vec.register_dependencies("a", vec!["b", "c", "d"]);
+        from_vec.register_dependencies("c", vec![]);
+
+        let mut from_arr: DepGraph<&str> = DepGraph::new();
+        from_arr.register_dependencies_arr("a", ["b", "c", "d"]);
+        from_arr.register_dependencies_arr("c", []);
+
+        assert_eq!(from_vec.nodes, from_arr.nodes);
+        assert_eq!(from_vec.dependencies, from_arr.dependencies);
+    }
+
+    #[test]
+    fn solvent_test_would_create_cycle() {
+        let depgraph = branching_graph();
+        assert!(depgraph.would_create_cycle(&"c", &"a"));
+        assert!(depgraph.would_create_cycle(&"n", &"c"));
+        assert!(depgraph.would_create_cycle(&"b", &"b"));
+        assert!(!depgraph.would_create_cycle(&"a", &"n"));
+        assert!(!depgraph.would_create_cycle(&"b", &"c"));
+        assert!(!depgraph.would_create_cycle(&"z", &"a"));
+        assert!(!depgraph.would_create_cycle(&"a", &"z"));
+
+        // Nothing was added
+        assert_eq!(depgraph.nodes.len(), 14);
+    }
+
+    #[test]
+    fn solvent_test_try_register_dependency() {
+        let mut depgraph: DepGraph<&str> = DepGraph::new();
+        depgraph.try_register_dependency("a", "b").unwrap();
+        depgraph.try_register_dependency("b", "c").unwrap();
+        depgraph.try_register_dependency("a", "c").unwrap();
+
+        let before = depgraph.clone();
+        match depgraph.try_register_dependency("c", "a") {
+            Err(SolventError::CycleDetected(s)) => {
+                assert!(s == "[2, 0, 2]" || s == "[2, 0, 1, 2]");
+            }
+            other => panic!("Expected a cycle, got {:?}", other),
+        }
+        assert!(depgraph.try_register_dependency("c", "c").is_err());
+        assert_eq!(depgraph.nodes, before.nodes);
+        assert_eq!(depgraph.dependencies, before.dependencies);
+        assert!(depgraph.topological_sort().is_ok());
+
+        // A self dependency is refused even when the node does not exist yet
+        let mut depgraph: DepGraph<&str> = DepGraph::new();
+        assert_eq!(
+            depgraph.try_register_dependency("x", "x"),
+            Err(SolventError::CycleDetected("[0, 0]".to_string()))
+        );
+        assert!(depgraph.nodes.is_empty());
+        assert_eq!(depgraph.topological_sort(), Ok(vec![]));
+    }
+
+    #[test]
+    fn solvent_test_resolve_partial() {
+        let branching = branching_graph();
+        let (resolved, error) = branching.resolve_partial(&"a");
+        assert_eq!(resolved.len(), 14);
+        assert!(error.is_none());
+
+        let mut depgraph: DepGraph<&str> = DepGraph::new();
+        depgraph.register_dependency("a", "b");
+        depgraph.register_dependencies("b", vec!["leaf", "c"]);
+        depgraph.register_dependency("c", "d");
+        depgraph.register_dependency("d", "c");
+
+        let (resolved, error) = depgraph.resolve_partial(&"a");
+        assert!(matches!(error, Some(SolventError::CycleDetected(_))));
+        assert_eq!(resolved, vec![&"leaf"]);
+
+        let (resolved, error) = branching.resolve_partial(&"z");
+        assert!(resolved.is_empty());
+        assert_eq!(error, Some(SolventError::NoSuchNode));
+    }
+
+    #[test]
+    fn solvent_test_resolve_validated() {
+        let mut depgraph = stoppage_graph();
+        assert!(depgraph.phantom_nodes().is_empty());
+        assert_eq!(depgraph.resolve_validated(&"appconn").unwrap().len(), 10);
+
+        depgraph.register_dependency("adminconn", "password");
+        assert_eq!(depgraph.phantom_nodes(), vec![&"password"]);
+        assert_eq!(depgraph.resolve(&"appconn").unwrap().len(), 11);
+        assert_eq!(
+            depgraph.resolve_validated(&"appconn"),
+            Err(SolventError::NoSuchNode)
+        );
+        // Not reachable from appuser
+        assert!(depgraph.resolve_validated(&"appuser").is_ok());
+
+        // Phantom nodes below satisfied nodes are still found
+        depgraph.mark_as_satisfied(&["adminconn"]).unwrap();
+        assert_eq!(depgraph.resolve(&"appconn").unwrap().len(), 9);
+        assert_eq!(
+            depgraph.resolve_validated(&"appconn"),
+            Err(SolventError::NoSuchNode)
+        );
+
+        depgraph.register_node("password");
+        assert!(depgraph.phantom_nodes().is_empty());
+        assert!(depgraph.resolve_validated(&"appconn").is_ok());
+    }
+
+    #[test]
+    fn solvent_test_ready_nodes() {
+        let mut depgraph = stoppage_graph();
+        assert_eq!(
+            depgraph.ready_nodes(&"appconn").unwrap(),
+            vec![&"superconn"]
+        );
+
+        depgraph
+            .mark_as_satisfied(&["superconn", "owneruser"])
+            .unwrap();
+        let ready = depgraph.ready_nodes(&"appconn").unwrap();
+        assert_eq!(ready.len(), 2);
+        assert!(ready.contains(&&"appuser"));
+        assert!(ready.contains(&&"database"));
+
+        depgraph
+            .mark_as_satisfied(&["appuser", "database"])
+            .unwrap();
+        let ready = depgraph.ready_nodes(&"appconn").unwrap();
+        assert_eq!(ready.len(), 2);
+        assert!(ready.contains(&&"ownerconn"));
+        assert!(ready.contains(&&"adminconn"));
+    }
+
+    #[test]
+    fn solvent_test_from_edge_lines() {
+        let input = "# comment\n\na: b c\nb: d\nc:\n";
+        let depgraph = DepGraph::from_edge_lines(input).unwrap();
+        assert_eq!(depgraph.nodes.len(), 4);
+        assert_eq!(depgraph.phantom_nodes(), vec![&"d".to_string()]);
+
+        assert_eq!(
+            DepGraph::from_edge_lines("a: b\nb c\n").err(),
+            Some(SolventError::ParseError(
+                2,
+                "expected `node: dependencies`".to_string()
+            ))
+        );
+        assert!(matches!(
+            DepGraph::from_edge_lines(" : b"),
+            Err(SolventError::ParseError(1, _))
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn solvent_test_from_reader() {
+        let input = "appconn: database appuser schemas\n\
+                     schemas: ownerconn appuser\n\
+                     ownerconn: database owneruser\n\
+                     database: owneruser\n\
+                     owneruser: superconn\n\
+                     appuser: superconn\n";
+        let depgraph = DepGraph::from_reader(std::io::Cursor::new(input)).unwrap();
+
+        let results = depgraph.resolve(&"appconn".to_string()).unwrap();
+        assert_eq!(results.len(), 7);
+        assert_eq!(results[0], "superconn");
+        assert_eq!(results[6], "appconn");
+
+        assert!(matches!(
+            DepGraph::from_reader(std::io::Cursor::new("a: b\n\noops\n")),
+            Err(SolventError::ParseError(3, _))
+        ));
+    }
+
+    #[test]
+    fn solvent_test_clear_dependencies_of() {
+        let mut depgraph = branching_graph();
+        depgraph.clear_dependencies_of(&"a").unwrap();
+        assert_eq!(depgraph.resolve(&"a").unwrap(), vec![&"a"]);
+        assert_eq!(depgraph.nodes.len(), 14);
+        assert_eq!(depgraph.resolve(&"b").unwrap(), vec![&"d", &"b"]);
+
+        assert_eq!(
+            depgraph.clear_dependencies_of(&"z"),
+            Err(SolventError::NoSuchNode)
+        );
+    }
+
+    #[test]
+    fn solvent_test_generations() {
+        let depgraph = branching_graph();
+        let generations = depgraph.generations().unwrap();
+        assert_eq!(generations.len(), 14);
+        assert_eq!(generations[0], (&"a", 7));
+        assert_eq!(generations[3], (&"d", 0));
+        assert_eq!(generations[12], (&"m", 1));
+        assert_eq!(generations[13], (&"n", 0));
+        let max = generations.iter().map(|(_, g)| *g).max().unwrap();
+        assert_eq!(max, 7);
+
+        let mut cyclic: DepGraph<&str> = DepGraph::new();
+        cyclic.register_dependency("a", "b");
+        cyclic.register_dependency("b", "a");
+        assert!(cyclic.generations().is_err());
+    }
+
+    #[test]
+    fn solvent_test_resolve_with_boundaries() {
+        let depgraph = stoppage_graph();
+
+        let results = depgraph
+            .resolve_with_boundaries(&["extensions"], &["database"])
+            .unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(*results[0], "database");
+        assert_eq!(*results[2], "extensions");
+        // owneruser and superconn are only reachable through database
+        assert!(!results.contains(&&"owneruser"));
+        assert!(!results.contains(&&"superconn"));
+
+        // owneruser is also reachable through ownerconn
+        let results = depgraph
+            .resolve_with_boundaries(&["extensions", "ownerconn"], &["database"])
+            .unwrap();
+        assert_eq!(results.len(), 6);
+        assert!(results.contains(&&"owneruser"));
+        assert!(results.contains(&&"superconn"));
+
+        let results = depgraph
+            .resolve_with_boundaries(&["appconn"], &["schemas"])
+            .unwrap();
+        assert_eq!(results.len(), 6);
+        assert!(!results.contains(&&"ownerconn"));
+        assert!(!results.contains(&&"schema_table"));
+
+        assert_eq!(
+            depgraph.resolve_with_boundaries(&["appconn"], &["nope"]),
+            Err(SolventError::NoSuchNode)
+        );
+    }
+
+    #[test]
+    fn solvent_test_prune_empty_dependency_sets() {
+        let mut depgraph = stoppage_graph();
+        let superconn = depgraph._pos(&"superconn").unwrap();
+        let database = depgraph._pos(&"database").unwrap();
+        assert!(depgraph.dependencies.contains_key(&superconn));
+
+        depgraph.clear_dependencies_of(&"database").unwrap();
+        let before: Vec<&str> = depgraph
+            .resolve(&"adminconn")
+            .unwrap()
+            .into_iter()
+            .cloned()
+            .collect();
+        depgraph.prune_empty_dependency_sets();
+        assert!(!depgraph.dependencies.contains_key(&superconn));
+        assert!(!depgraph.dependencies.contains_key(&database));
+        assert_eq!(
+            depgraph.resolve(&"adminconn").unwrap(),
+            vec![&"database", &"adminconn"]
+        );
+        assert_eq!(before, vec!["database", "adminconn"]);
+        assert_eq!(depgraph.dependencies.len(), 8);
+    }
+
+    #[test]
+    fn solvent_test_resolve_indices() {
+        let depgraph = branching_graph();
+        let indices = depgraph.resolve_indices(&"c").unwrap();
+        assert_eq!(indices.len(), 11);
+        assert_eq!(*indices.last().unwrap(), 2);
+
+        let mapped: Vec<&&str> = indices
+            .iter()
+            .map(|i| depgraph.node_at(*i).unwrap())
+            .collect();
+        assert_eq!(mapped, depgraph.resolve(&"c").unwrap());
+
+        assert_eq!(depgraph.node_at(0), Some(&"a"));
+        assert_eq!(depgraph.node_at(14), None);
+    }
+
+    #[test]
+    fn solvent_test_resolve_in_waves_sorted() {
+        let depgraph = branching_graph();
+        let waves = depgraph.resolve_in_waves_sorted(&"a").unwrap();
+        assert_eq!(waves[0], vec![&"d", &"f", &"j", &"l", &"n"]);
+        for wave in waves.iter() {
+            assert!(wave.windows(2).all(|w| w[0] < w[1]));
+        }
+        assert_eq!(waves.last().unwrap(), &vec![&"a"]);
+    }
+
+    #[test]
+    fn solvent_test_register_edges() {
+        let mut individually: DepGraph<&str> = DepGraph::new();
+        individually.register_dependency("a", "b");
+        individually.register_dependency("a", "c");
+        individually.register_dependency("b", "c");
+
+        let mut from_slice: DepGraph<&str> = DepGraph::new();
+        from_slice.register_edges(&[("a", "b"), ("a", "c"), ("b", "c")]);
+
+        assert_eq!(individually.nodes, from_slice.nodes);
+        assert_eq!(individually.dependencies, from_slice.dependencies);
+        assert_eq!(from_slice.resolve(&"a").unwrap(), vec![&"c", &"b", &"a"]);
+    }
+
+    #[test]
+    fn solvent_test_reversed() {
+        let mut depgraph = stoppage_graph();
+        depgraph.mark_as_satisfied(&["appuser"]).unwrap();
+        let reversed = depgraph.reversed();
+
+        // Everything which depended upon adminconn, dependents first
+        let results = reversed.resolve(&"adminconn").unwrap();
+        assert_eq!(results.len(), 4);
+        assert_eq!(*results[0], "appconn");
+        assert_eq!(*results[1], "schemas");
+        assert_eq!(*results[2], "extensions");
+        assert_eq!(*results[3], "adminconn");
+
+        // Everything depends upon superconn, but appuser is satisfied
+        let results = reversed.resolve(&"superconn").unwrap();
+        assert_eq!(results.len(), 9);
+        assert!(!results.contains(&&"appuser"));
+    }
+
+    #[test]
+    fn solvent_test_reversed_phantoms() {
+        let mut depgraph: DepGraph<&str> = DepGraph::new();
+        depgraph.register_dependencies("a", vec!["b", "c"]);
+        depgraph.register_dependency("b", "c");
+        depgraph.register_node("d");
+        assert_eq!(depgraph.phantom_nodes(), vec![&"c"]);
+
+        // The leaf becomes a dependent and the root a phantom; the isolated node stays
+        // declared
+        let reversed = depgraph.reversed();
+        assert_eq!(reversed.phantom_nodes(), vec![&"a"]);
+    }
+
+    #[test]
+    fn solvent_test_check_satisfied_consistency() {
+        let mut depgraph = stoppage_graph();
+        assert_eq!(depgraph.check_satisfied_consistency(), Ok(()));
+
+        depgraph
+            .mark_as_satisfied(&["superconn", "owneruser"])
+            .unwrap();
+        assert_eq!(depgraph.check_satisfied_consistency(), Ok(()));
+
+        depgraph.mark_as_satisfied(&["adminconn"]).unwrap();
+        assert_eq!(
+            depgraph.check_satisfied_consistency(),
+            Err(vec![(&"adminconn", &"database")])
+        );
+    }
+
+    #[test]
+    fn solvent_test_resolve_with_deps() {
+        let mut depgraph = stoppage_graph();
+        depgraph
+            .mark_as_satisfied(&["owneruser", "appuser"])
+            .unwrap();
+
+        let results = depgraph.resolve_with_deps(&"appconn").unwrap();
+        assert_eq!(results.len(), 7);
+        for (i, (_, deps)) in results.iter().enumerate() {
+            for dep in deps.iter() {
+                assert!(results[..i].iter().any(|(n, _)| n == dep));
+            }
+        }
+
+        let (node, deps) = &results[6];
+        assert_eq!(**node, "appconn");
+        // appuser is satisfied
+        assert_eq!(deps.len(), 2);
+    }
+
+    #[test]
+    fn solvent_test_resolver() {
+        let depgraph = branching_graph();
+        let mut resolver = depgraph.resolver();
+
+        assert_eq!(resolver.resolve(&"b").unwrap(), vec![&"d", &"b"]);
+        let results = resolver.resolve(&"a").unwrap();
+        assert_eq!(results.len(), 12);
+        assert!(!results.contains(&&"b"));
+        assert!(!results.contains(&&"d"));
+        assert!(resolver.resolve(&"a").unwrap().is_empty());
+        assert_eq!(resolver.resolve(&"z"), Err(SolventError::NoSuchNode));
+    }
+
+    #[test]
+    fn solvent_test_postorder() {
+        let mut depgraph = stoppage_graph();
+        let postorder = depgraph.postorder(&"appconn").unwrap();
+        assert_eq!(postorder.len(), 10);
+        assert_eq!(postorder, depgraph.resolve(&"appconn").unwrap());
+
+        depgraph
+            .mark_as_satisfied(&["owneruser", "appuser"])
+            .unwrap();
+        assert_eq!(depgraph.postorder(&"appconn").unwrap().len(), 10);
+        assert_eq!(depgraph.resolve(&"appconn").unwrap().len(), 7);
+    }
+
+    #[test]
+    fn solvent_test_add_and_reverse() {
+        let mut forward: DepGraph<&str> = DepGraph::new();
+        let mut reverse: DepGraph<&str> = DepGraph::new();
+        forward.add_and_reverse(&mut reverse, "a", "b");
+        forward.add_and_reverse(&mut reverse, "b", "c");
+        forward.add_and_reverse(&mut reverse, "a", "c");
+
+        assert_eq!(forward.resolve(&"a").unwrap(), vec![&"c", &"b", &"a"]);
+        assert_eq!(reverse.resolve(&"c").unwrap(), vec![&"a", &"b", &"c"]);
+        assert_eq!(
+            forward.reversed().structural_fingerprint(),
+            reverse.structural_fingerprint()
+        );
+    }
+
+    #[test]
+    fn solvent_test_new_acyclic() {
+        let mut depgraph: DepGraph<usize> = DepGraph::new_acyclic();
+
+        // Registering a chain top down forces the order to be rearranged each time
+        for i in 0..200 {
+            depgraph.try_register_dependency(i, i + 1).unwrap();
+            depgraph.try_register_dependency(i, i + 2).unwrap();
+        }
+        for i in 0..200 {
+            assert!(depgraph.try_register_dependency(i + 1, i).is_err());
+            assert!(depgraph.try_register_dependency(i + 2, i).is_err());
+        }
+        assert!(depgraph.try_register_dependency(7, 7).is_err());
+        assert!(depgraph.try_register_dependency(0, 150).is_ok());
+        assert_eq!(depgraph.nodes.len(), 202);
+        assert_eq!(depgraph.dependencies[&0].len(), 3);
+        assert_eq!(depgraph.dependencies[&1].len(), 2);
+
+        // The maintained order is a topological order
+        let order = depgraph.acyclic.as_ref().unwrap();
+        for (n, deplist) in depgraph.dependencies.iter() {
+            for dep in deplist.iter() {
+                assert!(order.rank[*dep] < order.rank[*n]);
+            }
+        }
+
+        match depgraph.try_register_dependency(3, 0) {
+            Err(SolventError::CycleDetected(s)) => {
+                assert!(s.starts_with("[3, 0, ") && s.ends_with(", 3]"));
+            }
+            other => panic!("Expected a cycle, got {:?}", other),
+        }
+
+        let results = depgraph.resolve(&198).unwrap();
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[2..], [&199, &198]);
+
+        let mut depgraph: DepGraph<&str> = DepGraph::new_acyclic();
+        depgraph.register_dependency("a", "x");
+        depgraph.register_dependency("x", "b");
+        depgraph.register_dependency("y", "c");
+        assert!(depgraph.merge_nodes(&"a", &"b").is_err());
+        depgraph.merge_nodes(&"x", &"c").unwrap();
+        assert!(depgraph.try_register_dependency("x", "y").is_err());
+        assert_eq!(depgraph.resolve(&"y").unwrap(), vec![&"b", &"x", &"y"]);
+    }
+
+    #[test]
+    fn solvent_test_new_acyclic_fallible() {
+        let mut depgraph: DepGraph<&str> = DepGraph::new_acyclic();
+        assert!(depgraph.try_register_dependency("x", "x").is_err());
+        assert!(depgraph.nodes.is_empty());
+
+        depgraph.register_dependency("a", "b");
+        depgraph.register_node("c");
+        let before = depgraph.clone();
+        assert!(depgraph
+            .try_register_dependencies("b", vec!["c", "a"])
+            .is_err());
+        assert!(depgraph
+            .register_dependencies_bounded("b", vec!["c", "a"], 5)
+            .is_err());
+        assert!(depgraph
+            .try_register_edges(&[("b", "c"), ("c", "a")])
+            .is_err());
+        assert!(depgraph
+            .try_register_edges(&[("c", "e"), ("e", "f"), ("b", "a")])
+            .is_err());
+        assert_eq!(depgraph.declared, before.declared);
+        assert!(depgraph.node("b").try_depends_on("a").is_err());
+        assert_eq!(depgraph.nodes, before.nodes);
+        assert_eq!(depgraph.dependencies, before.dependencies);
+        assert_eq!(
+            depgraph.acyclic.as_ref().unwrap().at_rank,
+            before.acyclic.as_ref().unwrap().at_rank
+        );
+
+        depgraph
+            .try_register_edges(&[("b", "c"), ("c", "d")])
+            .unwrap();
+        depgraph.node("a").try_depends_on("d").unwrap();
+        assert_eq!(
+            depgraph.resolve(&"a").unwrap(),
+            vec![&"d", &"c", &"b", &"a"]
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn solvent_test_new_acyclic_panics_unchanged() {
+        let mut depgraph: DepGraph<&str> = DepGraph::new_acyclic();
+        depgraph.register_dependency("a", "b");
+        let before = depgraph.clone();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            depgraph.register_dependencies("b", vec!["c", "a"]);
+        }));
+        assert!(result.is_err());
+        assert_eq!(depgraph.nodes, before.nodes);
+        assert_eq!(depgraph.dependencies, before.dependencies);
+    }
+
+    #[test]
+    #[should_panic]
+    fn solvent_test_new_acyclic_panics() {
+        let mut depgraph: DepGraph<&str> = DepGraph::new_acyclic();
+        depgraph.register_dependencies("a", vec!["b", "c"]);
+        depgraph.register_dependency("c", "d");
+        depgraph.register_dependency("d", "a");
+    }
+
+    #[test]
+    fn solvent_test_nodes_in_rank_range() {
+        let depgraph = branching_graph();
+        let order = depgraph.resolve(&"a").unwrap();
+
+        assert_eq!(
+            depgraph.nodes_in_rank_range(&"a", 0, 3).unwrap(),
+            order[0..3]
+        );
+        assert_eq!(
+            depgraph.nodes_in_rank_range(&"a", 12, 20).unwrap(),
+            order[12..]
+        );
+        assert!(depgraph
+            .nodes_in_rank_range(&"a", 20, 30)
+            .unwrap()
+            .is_empty());
+        assert!(depgraph.nodes_in_rank_range(&"a", 5, 2).unwrap().is_empty());
+    }
+
+    #[test]
+    fn solvent_test_edge_index_list() {
+        let depgraph = branching_graph();
+        let edges = depgraph.edge_index_list();
+        assert_eq!(edges.len(), 15);
+        assert_eq!(edges[0], (0, 1));
+
+        let rebuilt =
+            DepGraph::from_node_and_edge_indices(depgraph.nodes.clone(), edges.clone()).unwrap();
+        assert_eq!(rebuilt.nodes, depgraph.nodes);
+        assert_eq!(rebuilt.dependencies, depgraph.dependencies);
+        assert_eq!(rebuilt.edge_index_list(), edges);
+
+        assert_eq!(
+            DepGraph::from_node_and_edge_indices(vec!["a", "b"], vec![(0, 2)]).err(),
+            Some(SolventError::NoSuchNode)
+        );
+        assert_eq!(
+            DepGraph::from_node_and_edge_indices(vec!["a", "a"], vec![]).err(),
+            Some(SolventError::DuplicateNode)
+        );
+    }
+
+    #[test]
+    fn solvent_test_resolve_or_empty() {
+        let mut depgraph = branching_graph();
+        assert!(depgraph.resolve_or_empty(&"z").is_empty());
+        assert_eq!(depgraph.resolve_or_empty(&"b"), vec![&"d", &"b"]);
+
+        depgraph.register_dependency("d", "a");
+        assert!(depgraph.resolve_or_empty(&"b").is_empty());
+    }
+
+    #[test]
+    fn solvent_test_snapshot_satisfied() {
+        let mut depgraph = stoppage_graph();
+        depgraph
+            .mark_as_satisfied(&["superconn", "owneruser"])
+            .unwrap();
+        let snap = depgraph.snapshot_satisfied();
+
+        depgraph
+            .mark_as_satisfied(&["database", "appuser"])
+            .unwrap();
+        assert_eq!(depgraph.resolve(&"appconn").unwrap().len(), 6);
+
+        depgraph.restore_satisfied(snap);
+        assert_eq!(depgraph.satisfied.len(), 2);
+        assert_eq!(depgraph.resolve(&"appconn").unwrap().len(), 8);
+    }
+
+    #[test]
+    fn solvent_test_restore_stale_snapshot() {
+        let mut depgraph = branching_graph();
+        depgraph.mark_as_satisfied(&["i", "n"]).unwrap();
+        let snap = depgraph.snapshot_satisfied();
+
+        depgraph.merge_nodes(&"i", &"n").unwrap();
+        depgraph.restore_satisfied(snap);
+        assert!(depgraph.validate_integrity().is_ok());
+
+        let mut other: DepGraph<&str> = DepGraph::new();
+        other.register_node("a");
+        other.copy_satisfied_from(&depgraph);
+    }
+
+    #[test]
+    fn solvent_test_requirement_diff() {
+        let depgraph = branching_graph();
+        let diff = depgraph.requirement_diff(&"a", &"c").unwrap();
+        assert_eq!(diff.len(), 3);
+        assert!(diff.contains(&&"b"));
+        assert!(diff.contains(&&"d"));
+        assert!(!diff.contains(&&"e"));
+        assert!(!diff.contains(&&"g"));
+        assert_eq!(*diff[2], "a");
+
+        assert!(depgraph.requirement_diff(&"c", &"a").unwrap().is_empty());
+        assert_eq!(
+            depgraph.requirement_diff(&"a", &"z"),
+            Err(SolventError::NoSuchNode)
+        );
+    }
+
+    #[test]
+    fn solvent_test_resolve_status() {
+        let mut depgraph = branching_graph();
+        assert_eq!(
+            depgraph.resolve_status(&"b").unwrap(),
+            ResolveStatus::Pending(vec![&"d", &"b"])
+        );
+
+        depgraph.mark_as_satisfied(&["a"]).unwrap();
+        assert!(depgraph.resolve(&"a").unwrap().is_empty());
+        assert_eq!(
+            depgraph.resolve_status(&"a").unwrap(),
+            ResolveStatus::AlreadySatisfied
+        );
+        assert_eq!(depgraph.resolve_status(&"z"), Err(SolventError::NoSuchNode));
+    }
+
+    #[test]
+    fn solvent_test_critical_path() {
+        let depgraph = branching_graph();
+
+        // A unit cost makes the longest chain the critical path
+        let (total, path) = depgraph.critical_path(&"a", |_| 1).unwrap();
+        assert_eq!(total, 8);
+        assert_eq!(path, vec![&"n", &"m", &"k", &"i", &"h", &"g", &"c", &"a"]);
+
+        // Make the short branch through e and f the most expensive
+        let (total, path) = depgraph
+            .critical_path(&"a", |n| if *n == "f" { 100 } else { 1 })
+            .unwrap();
+        assert_eq!(total, 103);
+        assert_eq!(path, vec![&"f", &"e", &"c", &"a"]);
+
+        let (total, path) = depgraph.critical_path(&"a", |_| u64::MAX).unwrap();
+        assert_eq!(total, u64::MAX);
+        assert_eq!(path.last(), Some(&&"a"));
+
+        let mut cyclic: DepGraph<&str> = DepGraph::new();
+        cyclic.register_dependency("a", "b");
+        cyclic.register_dependency("b", "a");
+        assert!(cyclic.critical_path(&"a", |_| 1).is_err());
+    }
+
+    #[test]
+    fn solvent_test_freeze() {
+        let mut depgraph = branching_graph();
+        depgraph.mark_as_satisfied(&["h"]).unwrap();
+
+        let frozen = depgraph.clone().freeze();
+        for node in ["a", "b", "c", "g", "h", "n"].iter() {
+            assert_eq!(frozen.resolve(node), depgraph.resolve(node));
+        }
+        assert_eq!(frozen.topological_sort(), depgraph.topological_sort());
+        assert_eq!(frozen.resolve(&"z"), Err(SolventError::NoSuchNode));
+
+        let mut dependents = frozen.dependents_of(&"m").unwrap();
+        dependents.sort();
+        assert_eq!(dependents, vec![&"c", &"k"]);
+        assert!(frozen.dependents_of(&"a").unwrap().is_empty());
+    }
+
+    #[test]
+    fn solvent_test_has_dependency() {
+        let depgraph = branching_graph();
+        assert!(depgraph.has_dependency(&"a", &"b"));
+        assert!(depgraph.has_dependency(&"m", &"n"));
+        assert!(!depgraph.has_dependency(&"a", &"n"));
+        assert!(!depgraph.has_dependency(&"b", &"a"));
+        assert!(!depgraph.has_dependency(&"a", &"z"));
+        assert!(!depgraph.has_dependency(&"z", &"a"));
+    }
+
+    #[test]
+    fn solvent_test_describe() {
+        let depgraph = branching_graph();
+        let description = depgraph.describe();
+        assert!(description.contains("nodes: 14"));
+        assert!(description.contains("edges: 15"));
+        assert!(description.contains("roots: 1"));
+        assert!(description.contains("leaves: 5"));
+        assert!(description.contains("acyclic: true"));
+        assert!(description.contains("longest chain: 8"));
+
+        let mut cyclic: DepGraph<&str> = DepGraph::new();
+        cyclic.register_dependency("a", "b");
+        cyclic.register_dependency("b", "a");
+        assert!(cyclic.describe().contains("acyclic: false"));
+    }
+
+    #[test]
+    fn solvent_test_dependencies_of_by() {
+        let depgraph = branching_graph();
+        let order = depgraph.dependencies_of_by(&"a", |x, y| y.cmp(x)).unwrap();
+        assert_eq!(
+            order,
+            vec![
+                &"d", &"n", &"m", &"l", &"k", &"j", &"i", &"h", &"g", &"f", &"e", &"c", &"b", &"a"
+            ]
+        );
+    }
+
+    #[test]
+    fn solvent_test_replace_dependency_target() {
+        let mut depgraph = branching_graph();
+        assert_eq!(depgraph.replace_dependency_target(&"d", "x"), Ok(2));
+        assert!(depgraph.has_dependency(&"a", &"x"));
+        assert!(depgraph.has_dependency(&"b", &"x"));
+        assert!(!depgraph.has_dependency(&"a", &"d"));
+        assert!(!depgraph.has_dependency(&"b", &"d"));
+
+        // d remains, but nothing depends upon it any more
+        let order = depgraph.resolve(&"a").unwrap();
+        assert!(order.contains(&&"x"));
+        assert!(!order.contains(&&"d"));
+
+        assert_eq!(
+            depgraph.replace_dependency_target(&"z", "y"),
+            Err(SolventError::NoSuchNode)
+        );
+
+        // A node does not come to depend upon itself
+        let mut depgraph: DepGraph<&str> = DepGraph::new();
+        depgraph.register_dependencies("a", vec!["old"]);
+        depgraph.register_dependencies("new", vec!["old"]);
+        assert_eq!(depgraph.replace_dependency_target(&"old", "new"), Ok(1));
+        assert!(depgraph.has_dependency(&"a", &"new"));
+        assert!(depgraph.has_dependency(&"new", &"old"));
+        assert_eq!(depgraph.resolve(&"a").unwrap(), vec![&"old", &"new", &"a"]);
+
+        // Rewrites which would create a cycle in an acyclic graph change nothing
+        let mut depgraph: DepGraph<&str> = DepGraph::new_acyclic();
+        depgraph.register_dependencies("a", vec!["old"]);
+        depgraph.register_dependencies("b", vec!["old"]);
+        depgraph.register_dependencies("new", vec!["b"]);
+        let before = depgraph.clone();
+        assert!(matches!(
+            depgraph.replace_dependency_target(&"old", "new"),
+            Err(SolventError::CycleDetected(_))
+        ));
+        assert_eq!(depgraph.dependencies, before.dependencies);
+        assert_eq!(depgraph.replace_dependency_target(&"old", "x"), Ok(2));
+        assert_eq!(depgraph.resolve(&"a").unwrap(), vec![&"x", &"a"]);
+    }
+
+    #[test]
+    fn solvent_test_iterator_batches() {
+        let depgraph = branching_graph();
+        let mut iter = depgraph.dependencies_of(&"a").unwrap();
+
+        let first: Vec<&str> = iter.by_ref().take(3).map(|n| *n.unwrap()).collect();
+        let second: Vec<&str> = iter.by_ref().take(3).map(|n| *n.unwrap()).collect();
+        assert_eq!(first.len(), 3);
+        assert_eq!(second.len(), 3);
+        assert!(first.iter().all(|n| !second.contains(n)));
+
+        // Together the batches form a prefix of a valid order: each node's dependencies
+        // come before it
+        let prefix: Vec<&str> = first.into_iter().chain(second).collect();
+        for (i, node) in prefix.iter().enumerate() {
+            for dep in depgraph.nodes.iter() {
+                if depgraph.has_dependency(node, dep) {
+                    assert!(prefix[..i].contains(dep));
+                }
+            }
+        }
+
+        // The rest of the walk is still available
+        assert_eq!(iter.count(), 14 - 6);
+    }
+
+    #[test]
+    fn solvent_test_approx_feedback_arcs() {
+        assert_eq!(branching_graph().approx_feedback_arcs(), 0);
+
+        let mut depgraph: DepGraph<&str> = DepGraph::new();
+        depgraph.register_dependency("a", "b");
+        depgraph.register_dependency("b", "c");
+        depgraph.register_dependency("c", "a");
+        assert_eq!(depgraph.approx_feedback_arcs(), 1);
+
+        depgraph.register_dependency("d", "d");
+        assert_eq!(depgraph.approx_feedback_arcs(), 2);
+    }
+
+    #[test]
+    fn solvent_test_set_node_value() {
+        let mut depgraph = branching_graph();
+        let id = depgraph.resolve_indices(&"c").unwrap().pop().unwrap();
+        assert_eq!(depgraph.set_node_value(id, "x"), Ok("c"));
+        assert_eq!(depgraph.node_at(id), Some(&"x"));
+        assert!(depgraph.has_dependency(&"a", &"x"));
+        assert!(depgraph.has_dependency(&"x", &"e"));
+        assert!(!depgraph.has_dependency(&"a", &"c"));
+
+        assert_eq!(
+            depgraph.set_node_value(id, "a"),
+            Err(SolventError::DuplicateNode)
+        );
+        assert_eq!(depgraph.set_node_value(id, "x"), Ok("x"));
+        assert_eq!(
+            depgraph.set_node_value(99, "y"),
+            Err(SolventError::NoSuchNode)
+        );
+    }
+
+    #[test]
+    fn solvent_test_resolve_everything() {
+        let mut depgraph = branching_graph();
+        let order = depgraph.resolve_everything().unwrap();
+        assert_eq!(order.len(), 14);
+        assert_eq!(order.last(), Some(&&"a"));
+
+        // A second root, and a satisfied node which is pruned
+        depgraph.register_dependency("x", "d");
+        depgraph.mark_as_satisfied(&["b"]).unwrap();
+        let order = depgraph.resolve_everything().unwrap();
+        assert_eq!(order.len(), 14);
+        assert!(!order.contains(&&"b"));
+        assert_eq!(order.last(), Some(&&"x"));
+
+        let mut cyclic: DepGraph<&str> = DepGraph::new();
+        cyclic.register_dependency("a", "b");
+        cyclic.register_dependency("b", "a");
+        assert!(cyclic.resolve_everything().is_err());
+    }
+
+    #[test]
+    fn solvent_test_resolve_traced() {
+        let depgraph = branching_graph();
+        let events = core::cell::RefCell::new(Vec::new());
+        let order = depgraph
+            .resolve_traced(
+                &"a",
+                |n| events.borrow_mut().push((true, *n)),
+                |n| events.borrow_mut().push((false, *n)),
+            )
+            .unwrap();
+        assert_eq!(order, depgraph.resolve(&"a").unwrap());
+
+        // Each node is entered and left once, and the calls nest properly
+        let events = events.into_inner();
+        assert_eq!(events.len(), 28);
+        let mut stack = Vec::new();
+        for (enter, n) in events.iter() {
+            if *enter {
+                stack.push(*n);
+            } else {
+                assert_eq!(stack.pop(), Some(*n));
+            }
+        }
+        assert!(stack.is_empty());
+
+        // Leave order is the resolution order
+        let leaves: Vec<&str> = events.iter().filter(|e| !e.0).map(|e| e.1).collect();
+        assert_eq!(leaves, order.into_iter().cloned().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn solvent_test_outstanding_subgraph() {
+        let mut depgraph = stoppage_graph();
+        depgraph
+            .mark_as_satisfied(&["superconn", "owneruser", "appuser", "database"])
+            .unwrap();
+
+        let subgraph = depgraph.outstanding_subgraph(&"appconn").unwrap();
+        assert_eq!(subgraph.nodes.len(), 6);
+        for satisfied in ["superconn", "owneruser", "appuser", "database"].iter() {
+            assert!(!subgraph.nodes.contains(satisfied));
+        }
+        assert!(subgraph.has_dependency(&"appconn", &"schemas"));
+        assert!(subgraph.satisfied.is_empty());
+        assert_eq!(
+            subgraph.resolve(&"appconn").unwrap().len(),
+            depgraph.resolve(&"appconn").unwrap().len()
+        );
+
+        // Nothing is outstanding for a satisfied target
+        let subgraph = depgraph.outstanding_subgraph(&"database").unwrap();
+        assert!(subgraph.nodes.is_empty());
+    }
+
+    #[test]
+    fn solvent_test_condensation() {
+        let mut depgraph: DepGraph<&str> = DepGraph::new();
+        depgraph.register_dependency("a", "b");
+        depgraph.register_dependency("b", "c");
+        depgraph.register_dependency("c", "d");
+        depgraph.register_dependency("d", "b");
+        depgraph.register_dependency("d", "e");
+        assert!(depgraph.topological_sort().is_err());
+
+        let condensed = depgraph.condensation();
+        assert_eq!(condensed.nodes.len(), 3);
+        assert!(condensed.has_dependency(&vec!["a"], &vec!["b", "c", "d"]));
+        assert!(condensed.has_dependency(&vec!["b", "c", "d"], &vec!["e"]));
+        assert_eq!(
+            condensed.topological_sort().unwrap(),
+            vec![&vec!["e"], &vec!["b", "c", "d"], &vec!["a"]]
+        );
+    }
+
+    #[test]
+    fn solvent_test_affordable_dependencies() {
+        let depgraph = branching_graph();
+        let order = depgraph.resolve(&"a").unwrap();
+
+        let affordable = depgraph.affordable_dependencies(&"a", 3, |_| 1).unwrap();
+        assert_eq!(affordable, order[..3].to_vec());
+
+        // Stops at the first node which doesn't fit, even if later ones would
+        let first = *order[0];
+        let affordable = depgraph
+            .affordable_dependencies(&"a", 5, |n| if *n == first { 10 } else { 1 })
+            .unwrap();
+        assert!(affordable.is_empty());
+
+        assert_eq!(
+            depgraph
+                .affordable_dependencies(&"a", u64::MAX, |_| 1)
+                .unwrap(),
+            order
+        );
+    }
+
+    #[test]
+    fn solvent_test_resolve_dependents() {
+        let depgraph = stoppage_graph();
+        let order = depgraph.resolve_dependents(&"database").unwrap();
+        assert_eq!(order.len(), 7);
+        assert_eq!(order.first(), Some(&&"database"));
+        assert_eq!(order.last(), Some(&&"appconn"));
+        assert!(!order.contains(&&"appuser"));
+
+        // Each node follows the nodes it depends upon
+        for (i, node) in order.iter().enumerate() {
+            for earlier in order[i + 1..].iter() {
+                assert!(!depgraph.has_dependency(node, earlier));
+            }
+        }
+
+        assert_eq!(
+            depgraph.resolve_dependents(&"z"),
+            Err(SolventError::NoSuchNode)
+        );
+    }
+
+    // A copy of the graph with every dependency set stored as a HashSet, as they were
+    // before small sets were stored inline
+    fn hashed_dependencies(depgraph: &DepGraph<&'static str>) -> DepGraph<&'static str> {
+        let mut hashed = depgraph.clone();
+        for deplist in hashed.dependencies.values_mut() {
+            *deplist = DepSet::Large(deplist.iter().cloned().collect());
+        }
+        hashed
+    }
+
+    #[test]
+    fn solvent_test_small_dependency_sets() {
+        let mut set = DepSet::default();
+        for pos in 0..20 {
+            assert!(set.insert(pos));
+            assert!(!set.insert(pos));
+            assert_eq!(matches!(set, DepSet::Small(_)), pos < 8);
+        }
+        assert_eq!(set.len(), 20);
+        assert!((0..20).all(|pos| set.contains(&pos)));
+        assert!(set.remove(&7));
+        assert!(!set.remove(&7));
+        assert!(!set.contains(&7));
+
+        // Clearing keeps the storage for reuse
+        set.clear();
+        assert!(set.is_empty());
+        match set {
+            DepSet::Large(ref hashed) => assert!(hashed.capacity() >= 19),
+            DepSet::Small(_) => panic!("cleared set changed storage"),
+        }
+
+        // Resolution is the same whichever way the sets are stored
+        let mut depgraph = branching_graph();
+        let many: Vec<&str> = vec!["o", "p", "q", "r", "s", "t", "u", "v", "w", "x"];
+        depgraph.register_dependencies("n", many.clone());
+        assert!(matches!(depgraph.dependencies[&13], DepSet::Large(_)));
+        let hashed = hashed_dependencies(&depgraph);
+        for target in ["a", "c", "n"].iter() {
+            let inline: Vec<&str> = depgraph
+                .resolve(target)
+                .unwrap()
+                .into_iter()
+                .cloned()
+                .collect();
+            let hashed: Vec<&str> = hashed
+                .resolve(target)
+                .unwrap()
+                .into_iter()
+                .cloned()
+                .collect();
+            assert!(depgraph.is_valid_order(target, &inline).unwrap());
+            assert!(depgraph.is_valid_order(target, &hashed).unwrap());
+        }
+    }
+
+    #[test]
+    fn solvent_test_max_degree_nodes() {
+        let depgraph = branching_graph();
+        assert_eq!(depgraph.max_out_degree_node(), Some((&"a", 3)));
+        assert_eq!(depgraph.max_in_degree_node(), Some((&"d", 2)));
+
+        let empty: DepGraph<&str> = DepGraph::new();
+        assert_eq!(empty.max_out_degree_node(), None);
+        assert_eq!(empty.max_in_degree_node(), None);
+    }
+
+    #[test]
+    fn solvent_test_resolve_strict() {
+        let mut depgraph: DepGraph<&str> = DepGraph::new();
+        depgraph.register_dependencies("a", vec!["b", "x"]);
+        depgraph.register_dependencies("b", vec!["y"]);
+        depgraph.register_node("c");
+        depgraph.register_dependency("d", "z");
+
+        assert_eq!(
+            depgraph.resolve_strict(&"a"),
+            Err(SolventError::PhantomNodes(vec![
+                "\"x\"".to_string(),
+                "\"y\"".to_string()
+            ]))
+        );
+        assert_eq!(depgraph.resolve_strict(&"c"), Ok(vec![&"c"]));
+
+        // Phantom nodes below satisfied nodes are still reported
+        depgraph.mark_as_satisfied(&["b"]).unwrap();
+        assert_eq!(depgraph.resolve(&"a").unwrap(), vec![&"x", &"a"]);
+        assert_eq!(
+            depgraph.resolve_strict(&"a"),
+            Err(SolventError::PhantomNodes(vec![
+                "\"x\"".to_string(),
+                "\"y\"".to_string()
+            ]))
+        );
+
+        depgraph.register_nodes(vec!["x", "y"]);
+        assert_eq!(depgraph.resolve_strict(&"a"), Ok(vec![&"x", &"a"]));
+    }
+
+    #[test]
+    fn solvent_test_resolve_with_pinned_last() {
+        let depgraph = branching_graph();
+        let order = depgraph.resolve_with_pinned_last(&"a", &["b"]).unwrap();
+        let order: Vec<&str> = order.into_iter().cloned().collect();
+        assert!(depgraph.is_valid_order(&"a", &order).unwrap());
+        assert_eq!(order[12..], ["b", "a"]);
+
+        // A pinned node which others depend upon is held back only as far as they allow
+        let order = depgraph
+            .resolve_with_pinned_last(&"a", &["m", "b"])
+            .unwrap();
+        let order: Vec<&str> = order.into_iter().cloned().collect();
+        assert!(depgraph.is_valid_order(&"a", &order).unwrap());
+        let m = order.iter().position(|n| *n == "m").unwrap();
+        assert_eq!(order[m + 1], "k");
+        // b is only needed by a, so it waits for everything else a needs
+        assert_eq!(
+            order,
+            ["d", "f", "e", "n", "j", "l", "m", "k", "i", "h", "g", "c", "b", "a"]
+        );
+
+        assert_eq!(
+            depgraph.resolve_with_pinned_last(&"a", &["z"]),
+            Err(SolventError::NoSuchNode)
+        );
+    }
+
+    #[test]
+    fn solvent_test_resolve_with_metrics() {
+        let mut depgraph = branching_graph();
+        let (order, metrics) = depgraph.resolve_with_metrics(&"a").unwrap();
+        assert_eq!(order, depgraph.resolve(&"a").unwrap());
+        assert_eq!(metrics.visits, 16);
+        assert!(metrics.visits >= depgraph.nodes.len());
+        assert_eq!(metrics.prunes, 0);
+        // Whether m is first reached through c or through k depends upon ordering
+        assert!(metrics.max_depth == 7 || metrics.max_depth == 8);
+
+        depgraph.mark_as_satisfied(&["d", "h"]).unwrap();
+        let (order, metrics) = depgraph.resolve_with_metrics(&"a").unwrap();
+        assert_eq!(order.len(), 8);
+        assert_eq!(metrics.prunes, 3);
+        assert_eq!(metrics.max_depth, 4);
+    }
+
+    #[test]
+    fn solvent_test_resolve_filtered() {
+        let depgraph = branching_graph();
+        let is_vowel = |n: &&str| ["a", "e", "i", "o", "u"].contains(n);
+        let vowels = depgraph.resolve_filtered(&"a", is_vowel).unwrap();
+        assert_eq!(vowels.len(), 3);
+        assert_eq!(vowels.last(), Some(&&"a"));
+
+        // Relative order is that of the full resolution
+        let order = depgraph.resolve(&"a").unwrap();
+        let expected: Vec<&&str> = order.into_iter().filter(|n| is_vowel(n)).collect();
+        assert_eq!(vowels, expected);
+    }
+
+    #[test]
+    fn solvent_test_dependencies_of_seeded() {
+        let depgraph = branching_graph();
+        let run = |seed: u64| -> Vec<&str> {
+            depgraph
+                .dependencies_of_seeded(&"a", seed)
+                .unwrap()
+                .map(|n| *n.unwrap())
+                .collect()
+        };
+
+        let orders: Vec<Vec<&str>> = (0..20).map(run).collect();
+        for (seed, order) in orders.iter().enumerate() {
+            assert_eq!(*order, run(seed as u64));
+            assert!(depgraph.is_valid_order(&"a", order).unwrap());
+        }
+        assert!(orders.iter().any(|order| *order != orders[0]));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn solvent_test_into_io_error() {
+        use std::io::ErrorKind;
+
+        let e: std::io::Error = SolventError::NoSuchNode.into();
+        assert_eq!(e.kind(), ErrorKind::NotFound);
+        assert_eq!(e.to_string(), "No Such Node");
+
+        let mut depgraph: DepGraph<&str> = DepGraph::new();
+        depgraph.register_dependency("a", "b");
+        depgraph.register_dependency("b", "a");
+        let e: std::io::Error = depgraph.resolve(&"a").unwrap_err().into();
+        assert_eq!(e.kind(), ErrorKind::InvalidData);
+        assert!(e.to_string().starts_with("Cycle Detected"));
+        assert!(e.get_ref().unwrap().is::<SolventError>());
+    }
+
+    #[test]
+    fn solvent_test_from_btreemap() {
+        let mut map = alloc::collections::BTreeMap::new();
+        map.insert("c", vec!["a", "z"]);
+        map.insert("a", vec!["b"]);
+        map.insert("b", vec![]);
+
+        let depgraph = DepGraph::from(map);
+        assert_eq!(depgraph.node_at(0), Some(&"a"));
+        assert_eq!(depgraph.node_at(1), Some(&"b"));
+        assert_eq!(depgraph.node_at(2), Some(&"c"));
+        assert_eq!(depgraph.node_at(3), Some(&"z"));
+        assert_eq!(depgraph.phantom_nodes(), vec![&"z"]);
+        assert_eq!(depgraph.resolve_indices(&"a").unwrap(), vec![1, 0]);
+    }
+
+    #[test]
+    fn solvent_test_register_provides() {
+        let mut depgraph: DepGraph<&str> = DepGraph::new();
+        depgraph.register_dependencies("mailer", vec!["mta", "libc"]);
+        depgraph.register_dependency("postfix", "libc");
+        depgraph.register_provides(&"postfix", "mta").unwrap();
+        depgraph.register_dependencies("cron", vec!["mta"]);
+
+        // Both dependents, before and after the alias was registered, get postfix
+        assert_eq!(depgraph.nodes.len(), 4);
+        assert!(depgraph.has_dependency(&"mailer", &"postfix"));
+        assert!(depgraph.has_dependency(&"cron", &"postfix"));
+        let order = depgraph.resolve(&"mailer").unwrap();
+        assert_eq!(order.len(), 3);
+        assert!(order.contains(&&"postfix"));
+        assert_eq!(
+            depgraph.resolve(&"cron").unwrap(),
+            vec![&"libc", &"postfix", &"cron"]
+        );
+        assert_eq!(depgraph.resolve(&"mta").unwrap(), vec![&"libc", &"postfix"]);
+
+        depgraph.register_provides(&"postfix", "mta").unwrap();
+        depgraph.register_node("exim");
+        assert_eq!(
+            depgraph.register_provides(&"exim", "mta"),
+            Err(SolventError::DuplicateNode)
+        );
+        assert_eq!(
+            depgraph.register_provides(&"sendmail", "mta"),
+            Err(SolventError::NoSuchNode)
+        );
+    }
+
+    #[test]
+    fn solvent_test_into_iter_topological() {
+        let depgraph = branching_graph();
+        let mut owned: DepGraph<String> = DepGraph::new();
+        for (node, dep) in depgraph.edge_index_list().into_iter() {
+            owned.register_dependency(
+                depgraph.nodes[node].to_string(),
+                depgraph.nodes[dep].to_string(),
+            );
+        }
+
+        let mut iter = owned.clone().into_iter_topological();
+        let mut order: Vec<String> = Vec::new();
+        for node in iter.by_ref().take(5) {
+            order.push(node.unwrap());
+        }
+        order.extend(iter.map(|n| n.unwrap()));
+        assert_eq!(order.len(), 14);
+        for (i, node) in order.iter().enumerate() {
+            for later in order[i + 1..].iter() {
+                assert!(!owned.has_dependency(node, later));
+            }
+        }
+
+        let mut cyclic: DepGraph<String> = DepGraph::new();
+        cyclic.register_dependency("a".to_string(), "b".to_string());
+        cyclic.register_dependency("b".to_string(), "a".to_string());
+        let results: Vec<_> = cyclic.into_iter_topological().collect();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].is_err());
+
+        // The same order as `into_topological_order`, with nodes before a cycle yielded
+        // ahead of the error
+        let expected = owned.clone().into_topological_order().unwrap();
+        let order: Vec<String> = owned.into_iter_topological().map(|n| n.unwrap()).collect();
+        assert_eq!(order, expected);
+
+        let mut cyclic: DepGraph<String> = DepGraph::new();
+        cyclic.register_node("x".to_string());
+        cyclic.register_dependency("a".to_string(), "b".to_string());
+        cyclic.register_dependency("b".to_string(), "a".to_string());
+        let mut iter = cyclic.into_iter_topological();
+        assert_eq!(iter.next(), Some(Ok("x".to_string())));
+        assert!(matches!(
+            iter.next(),
+            Some(Err(SolventError::CycleDetected(_)))
+        ));
+        assert_eq!(iter.next(), None);
     }
 
     #[test]
-    fn solvent_test_satisfied_stoppage() {
+    fn solvent_test_enforce_max_out_degree() {
+        let mut depgraph = branching_graph();
+        assert_eq!(depgraph.enforce_max_out_degree(3), Ok(()));
+        assert_eq!(depgraph.enforce_max_out_degree(2), Err((&"a", 3)));
+
+        depgraph.register_dependencies("x", vec!["a", "b", "c", "d", "e"]);
+        depgraph.clear_dependencies_of(&"a").unwrap();
+        depgraph.clear_dependencies_of(&"c").unwrap();
+        assert_eq!(depgraph.enforce_max_out_degree(3), Err((&"x", 5)));
+    }
+
+    #[test]
+    fn solvent_test_distance() {
+        let depgraph = branching_graph();
+        assert_eq!(depgraph.distance(&"a", &"n"), Ok(Some(3)));
+        assert_eq!(depgraph.distance(&"a", &"d"), Ok(Some(1)));
+        assert_eq!(depgraph.distance(&"a", &"l"), Ok(Some(6)));
+        assert_eq!(depgraph.distance(&"a", &"a"), Ok(Some(0)));
+        assert_eq!(depgraph.distance(&"n", &"a"), Ok(None));
+        assert_eq!(depgraph.distance(&"a", &"z"), Err(SolventError::NoSuchNode));
+    }
+
+    #[cfg(feature = "bincode")]
+    #[test]
+    fn solvent_test_bincode_round_trip() {
+        let mut depgraph: DepGraph<String> = DepGraph::new();
+        let branching = branching_graph();
+        for (node, dep) in branching.edge_index_list().into_iter() {
+            depgraph.register_dependency(
+                branching.nodes[node].to_string(),
+                branching.nodes[dep].to_string(),
+            );
+        }
+        depgraph.mark_as_satisfied(&["h".to_string()]).unwrap();
+        depgraph
+            .tag_node(&"b".to_string(), "optional".to_string())
+            .unwrap();
+
+        let bytes = depgraph.to_bytes().unwrap();
+        let restored: DepGraph<String> = DepGraph::from_bytes(&bytes).unwrap();
+        assert_eq!(restored.nodes, depgraph.nodes);
+        assert_eq!(restored.edge_index_list(), depgraph.edge_index_list());
+        assert_eq!(restored.satisfied, depgraph.satisfied);
+        assert_eq!(restored.tags, depgraph.tags);
+        assert_eq!(restored.phantom_nodes(), depgraph.phantom_nodes());
+        assert_eq!(restored.to_bytes().unwrap(), bytes);
+
+        assert!(matches!(
+            DepGraph::<String>::from_bytes(&bytes[..bytes.len() / 2]),
+            Err(SolventError::Serialization(_))
+        ));
+    }
+
+    #[test]
+    fn solvent_test_dependencies_only() {
+        let mut depgraph = branching_graph();
+        let order = depgraph.dependencies_only(&"a").unwrap();
+        assert_eq!(order.len(), 13);
+        assert!(!order.contains(&&"a"));
+        assert!(depgraph.dependencies_only(&"n").unwrap().is_empty());
+
+        // Nothing needs processing for a satisfied target
+        depgraph.mark_as_satisfied(&["a"]).unwrap();
+        assert!(depgraph.dependencies_only(&"a").unwrap().is_empty());
+    }
+
+    #[test]
+    fn solvent_test_retain_edges() {
+        let mut depgraph = branching_graph();
+        depgraph.retain_edges(|_, dep| *dep != "d");
+
+        assert!(!depgraph.has_dependency(&"a", &"d"));
+        assert!(!depgraph.has_dependency(&"b", &"d"));
+        assert!(depgraph.has_dependency(&"a", &"b"));
+        assert_eq!(depgraph.edge_index_list().len(), 13);
+
+        // d is still registered, but nothing needs it
+        assert_eq!(depgraph.resolve(&"d").unwrap(), vec![&"d"]);
+        assert!(!depgraph.resolve(&"a").unwrap().contains(&&"d"));
+    }
+
+    #[test]
+    fn solvent_test_rebuild() {
+        let mut depgraph = branching_graph();
+        depgraph.mark_as_satisfied(&["h"]).unwrap();
+        let rebuilt = depgraph.rebuild();
+        assert_eq!(rebuilt.nodes, depgraph.nodes);
+        assert_eq!(rebuilt.edge_index_list(), depgraph.edge_index_list());
+        assert_eq!(rebuilt.satisfied, depgraph.satisfied);
+        for node in depgraph.nodes.iter() {
+            assert_eq!(rebuilt.resolve(node), depgraph.resolve(node));
+        }
+
+        // A dependency set which grew large and then shrank is stored inline again
+        let many: Vec<&str> = vec!["o", "p", "q", "r", "s", "t", "u", "v", "w", "x"];
+        depgraph.register_dependencies("n", many);
+        depgraph.retain_edges(|node, dep| *node != "n" || *dep < "t");
+        assert!(matches!(depgraph.dependencies[&13], DepSet::Large(_)));
+        let rebuilt = depgraph.rebuild();
+        assert!(matches!(rebuilt.dependencies[&13], DepSet::Small(_)));
+        assert_eq!(rebuilt.resolve(&"a"), depgraph.resolve(&"a"));
+    }
+
+    #[test]
+    fn solvent_test_equivalent_groups() {
         let mut depgraph: DepGraph<&str> = DepGraph::new();
-        depgraph.register_dependencies("superconn", vec![]);
-        depgraph.register_dependencies("owneruser", vec!["superconn"]);
-        depgraph.register_dependencies("appuser", vec!["superconn"]);
-        depgraph.register_dependencies("database", vec!["owneruser"]);
-        depgraph.register_dependencies("ownerconn", vec!["database", "owneruser"]);
-        depgraph.register_dependencies("adminconn", vec!["database"]);
-        depgraph.register_dependencies("extensions", vec!["database", "adminconn"]);
-        depgraph.register_dependencies("schema_table", vec!["database", "ownerconn"]);
-        depgraph.register_dependencies(
-            "schemas",
-            vec!["ownerconn", "extensions", "schema_table", "appuser"],
+        depgraph.register_dependencies("a", vec!["b", "c"]);
+        depgraph.register_dependency("b", "d");
+        depgraph.register_dependency("c", "d");
+        depgraph.register_dependencies("e", vec!["c", "b"]);
+        depgraph.register_dependencies("f", vec!["b", "c", "d"]);
+        assert_eq!(
+            depgraph.equivalent_groups(),
+            vec![vec![&"a", &"e"], vec![&"b", &"c"]]
         );
-        depgraph.register_dependencies("appconn", vec!["database", "appuser", "schemas"]);
+
+        assert!(branching_graph().equivalent_groups().is_empty());
+    }
+
+    #[test]
+    fn solvent_test_resolve_round_robin() {
+        let depgraph = branching_graph();
+        assert_eq!(
+            depgraph.resolve_round_robin(&["b", "e"]).unwrap(),
+            vec![&"d", &"f", &"b", &"e"]
+        );
+
+        // Shared nodes are output once, and the order is valid for both targets
+        let order = depgraph.resolve_round_robin(&["a", "k"]).unwrap();
+        assert_eq!(order.len(), 14);
+        for (i, node) in order.iter().enumerate() {
+            for later in order[i + 1..].iter() {
+                assert!(!depgraph.has_dependency(node, later));
+            }
+        }
+    }
+
+    #[test]
+    fn solvent_test_nodes_at_generation() {
+        let depgraph = branching_graph();
+        assert_eq!(
+            depgraph.nodes_at_generation(0).unwrap(),
+            vec![&"d", &"f", &"j", &"l", &"n"]
+        );
+        assert_eq!(depgraph.nodes_at_generation(7).unwrap(), vec![&"a"]);
+        assert!(depgraph.nodes_at_generation(8).unwrap().is_empty());
+
+        let mut cyclic: DepGraph<&str> = DepGraph::new();
+        cyclic.register_dependency("a", "b");
+        cyclic.register_dependency("b", "a");
+        assert!(matches!(
+            cyclic.nodes_at_generation(0),
+            Err(SolventError::CycleDetected(_))
+        ));
+    }
+
+    #[test]
+    fn solvent_test_register_hint() {
+        let mut depgraph: DepGraph<&str> = DepGraph::new();
+        depgraph.register_dependencies("a", vec!["b", "c"]);
+        depgraph.register_node("d");
+        depgraph.register_hint("c", "b");
+        depgraph.register_hint("d", "b");
+        for _ in 0..10 {
+            assert_eq!(depgraph.resolve(&"a").unwrap(), vec![&"c", &"b", &"a"]);
+        }
+        assert_eq!(depgraph.resolve(&"b").unwrap(), vec![&"b"]);
+
+        // Dependencies win over hints
+        depgraph.register_hint("a", "c");
+        assert_eq!(depgraph.resolve(&"a").unwrap(), vec![&"c", &"b", &"a"]);
+
+        let frozen = depgraph.clone().freeze();
+        assert_eq!(frozen.resolve(&"a"), depgraph.resolve(&"a"));
+
+        // Every way of resolving follows the hints
+        let iterated: Vec<&&str> = depgraph
+            .dependencies_of(&"a")
+            .unwrap()
+            .map(|n| n.unwrap())
+            .collect();
+        assert_eq!(iterated, vec![&"c", &"b", &"a"]);
+        let (order, _) = depgraph.resolve_with_metrics(&"a").unwrap();
+        assert_eq!(order, vec![&"c", &"b", &"a"]);
+        let annotated: Vec<&&str> = depgraph
+            .resolve_annotated(&"a")
+            .unwrap()
+            .into_iter()
+            .map(|(n, _)| n)
+            .collect();
+        assert_eq!(annotated, vec![&"c", &"b", &"a"]);
+
+        // Hints do not add nodes, but apply once their nodes are registered
+        depgraph.register_hint("y", "x");
+        assert_eq!(depgraph.topological_sort().unwrap().len(), 4);
+        depgraph.register_dependencies("z", vec!["x", "y"]);
+        assert_eq!(depgraph.resolve(&"z").unwrap(), vec![&"y", &"x", &"z"]);
+    }
+
+    #[test]
+    fn solvent_test_register_hint_chain() {
+        // A long chain of hints running against the order the nodes would otherwise
+        // be output in
+        let mut depgraph: DepGraph<usize> = DepGraph::new();
+        depgraph.register_dependencies(1000, (0..1000).collect());
+        for n in 0..999 {
+            depgraph.register_hint(n + 1, n);
+        }
+        let order = depgraph.resolve(&1000).unwrap();
+        let mut expected: Vec<usize> = (0..1000).rev().collect();
+        expected.push(1000);
+        assert_eq!(order, expected.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn solvent_test_resolve_partial_with_hints() {
+        let mut depgraph: DepGraph<&str> = DepGraph::new();
+        depgraph.register_dependency("a", "b");
+        depgraph.register_dependencies("b", vec!["first", "second", "c"]);
+        depgraph.register_dependency("c", "d");
+        depgraph.register_dependency("d", "c");
+
+        // A hint unrelated to the target leaves resolution streaming
+        depgraph.register_dependency("x", "y");
+        depgraph.register_hint("y", "x");
+        let (resolved, error) = depgraph.resolve_partial(&"a");
+        assert!(matches!(error, Some(SolventError::CycleDetected(_))));
+        assert_eq!(resolved, vec![&"first", &"second"]);
+
+        // A hint which applies still keeps the nodes resolved before the cycle
+        depgraph.register_hint("second", "first");
+        let (resolved, error) = depgraph.resolve_partial(&"a");
+        assert!(matches!(error, Some(SolventError::CycleDetected(_))));
+        assert_eq!(resolved, vec![&"second", &"first"]);
+
+        let mut iter = depgraph.dependencies_of(&"a").unwrap();
+        assert_eq!(iter.next(), Some(Ok(&"second")));
+        assert_eq!(iter.next(), Some(Ok(&"first")));
+        assert!(matches!(
+            iter.next(),
+            Some(Err(SolventError::CycleDetected(_)))
+        ));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn solvent_test_prune_satisfied_subtrees() {
+        // "top" depends on "app", which depends on "lib", at the root of a wide tree of
+        // two hundred nodes
+        let mut depgraph: DepGraph<String> = DepGraph::new();
+        depgraph.register_dependency("top".to_string(), "app".to_string());
+        depgraph.register_dependency("app".to_string(), "lib".to_string());
+        for i in 0..20 {
+            let mid = format!("mid{}", i);
+            depgraph.register_dependency("lib".to_string(), mid.clone());
+            for j in 0..10 {
+                depgraph.register_dependency(mid.clone(), format!("leaf{}_{}", i, j));
+            }
+        }
+
+        let (order, naive) = depgraph.resolve_with_metrics(&"top".to_string()).unwrap();
+        assert_eq!(order.len(), 223);
+        assert_eq!(naive.visits, 223);
+
+        // With "lib" satisfied, none of the tree beneath it is visited
+        depgraph.mark_as_satisfied(&["lib".to_string()]).unwrap();
+        let (order, metrics) = depgraph.resolve_with_metrics(&"top".to_string()).unwrap();
+        assert_eq!(order, vec!["app", "top"]);
+        assert_eq!(metrics.visits, 3);
+        assert_eq!(metrics.prunes, 1);
+        assert!(metrics.visits * 50 < naive.visits);
+    }
+
+    #[test]
+    fn solvent_test_mark_as_satisfied_strict() {
+        let mut depgraph: DepGraph<&str> = DepGraph::new();
+        depgraph.register_dependencies("a", vec!["b"]);
+        depgraph.register_dependencies("b", vec!["c"]);
+
+        assert_eq!(
+            depgraph.mark_as_satisfied_strict(&["b"]),
+            Err(SolventError::DependenciesNotSatisfied(
+                "\"b\"".to_string(),
+                "\"c\"".to_string()
+            ))
+        );
+        // Nothing is marked when any node fails
+        assert_eq!(
+            depgraph.mark_as_satisfied_strict(&["c", "a"]),
+            Err(SolventError::DependenciesNotSatisfied(
+                "\"a\"".to_string(),
+                "\"b\"".to_string()
+            ))
+        );
+        assert_eq!(depgraph.resolve(&"a").unwrap(), vec![&"c", &"b", &"a"]);
+
+        // Nodes marked earlier in the same call count as satisfied
+        depgraph.mark_as_satisfied_strict(&["c", "b"]).unwrap();
+        assert_eq!(depgraph.resolve(&"a").unwrap(), vec![&"a"]);
+        assert_eq!(
+            depgraph.mark_as_satisfied_strict(&["z"]),
+            Err(SolventError::NoSuchNode)
+        );
+    }
+
+    #[test]
+    fn solvent_test_to_mermaid() {
+        let mut depgraph: DepGraph<&str> = DepGraph::new();
+        depgraph.register_dependency("a", "b");
+        depgraph.register_dependency("my app", "say \"hi\"");
+        depgraph.register_node("alone");
+
+        let mermaid = depgraph.to_mermaid();
+        assert!(mermaid.starts_with("graph TD\n"));
+        assert!(mermaid.contains("\n    a --> b\n"));
+        assert!(mermaid.contains("\n    my_app --> say__hi_\n"));
+        assert!(mermaid.contains("\n    my_app[\"my app\"]\n"));
+        assert!(mermaid.contains("\n    say__hi_[\"say #quot;hi#quot;\"]\n"));
+        assert!(mermaid.contains("\n    alone\n"));
+        assert_eq!(mermaid.lines().count(), 6);
+
+        // Nodes which sanitize to the same id are kept apart
+        depgraph.register_dependency("a b", "a-b");
+        let mermaid = depgraph.to_mermaid();
+        assert!(mermaid.contains("\n    a_b --> a_b_6\n"));
+
+        // Even when an id given a suffix is already another node's id
+        let mut depgraph: DepGraph<&str> = DepGraph::new();
+        depgraph.register_node("a_b_2");
+        depgraph.register_dependency("a b", "a-b");
+        let mermaid = depgraph.to_mermaid();
+        assert!(mermaid.contains("\n    a_b_2\n"));
+        assert!(mermaid.contains("\n    a_b --> a_b_2_2\n"));
+        assert!(mermaid.contains("\n    a_b_2_2[\"a-b\"]\n"));
+
+        // Keywords are not used as ids
+        let mut depgraph: DepGraph<&str> = DepGraph::new();
+        depgraph.register_dependency("start", "end");
+        let mermaid = depgraph.to_mermaid();
+        assert!(mermaid.contains("\n    end_1[\"end\"]\n"));
+        assert!(mermaid.contains("\n    start --> end_1\n"));
+    }
+
+    #[test]
+    fn solvent_test_register_dependencies_bounded() {
+        let mut depgraph: DepGraph<&str> = DepGraph::new();
+        assert_eq!(
+            depgraph.register_dependencies_bounded("a", vec!["b", "c", "d", "e", "f"], 3),
+            Err(SolventError::TooManyDependencies(5, 3))
+        );
+        assert!(depgraph.nodes.is_empty());
 
         depgraph
-            .mark_as_satisfied(&["owneruser", "appuser"])
+            .register_dependencies_bounded("a", vec!["b", "c"], 3)
             .unwrap();
-        assert_eq!(depgraph.satisfied.len(), 2);
+        assert_eq!(depgraph.resolve(&"a").unwrap().len(), 3);
+    }
 
-        let mut results: Vec<&str> = Vec::new();
+    #[test]
+    fn solvent_test_sorted_edges() {
+        let depgraph = branching_graph();
+        assert_eq!(
+            depgraph.sorted_edges(),
+            vec![
+                (&"a", &"b"),
+                (&"a", &"c"),
+                (&"a", &"d"),
+                (&"b", &"d"),
+                (&"c", &"e"),
+                (&"c", &"g"),
+                (&"c", &"m"),
+                (&"e", &"f"),
+                (&"g", &"h"),
+                (&"h", &"i"),
+                (&"i", &"j"),
+                (&"i", &"k"),
+                (&"k", &"l"),
+                (&"k", &"m"),
+                (&"m", &"n"),
+            ]
+        );
+        assert!(DepGraph::<&str>::new().sorted_edges().is_empty());
+    }
 
-        for node in depgraph.dependencies_of(&"appconn").unwrap() {
-            assert!(results.len() < 30);
-            match node {
-                Ok(n) => results.push(n),
-                Err(e) => panic!("Solvent error detected: {:?}", e),
-            };
+    #[test]
+    fn solvent_test_are_independent() {
+        let mut depgraph = branching_graph();
+        assert_eq!(depgraph.are_independent(&"b", &"c"), Ok(true));
+        assert_eq!(depgraph.are_independent(&"a", &"c"), Ok(false));
+        assert_eq!(depgraph.are_independent(&"c", &"a"), Ok(false));
+        assert_eq!(depgraph.are_independent(&"n", &"a"), Ok(false));
+        assert_eq!(depgraph.are_independent(&"c", &"c"), Ok(false));
+        assert_eq!(
+            depgraph.are_independent(&"a", &"z"),
+            Err(SolventError::NoSuchNode)
+        );
+
+        // A cycle elsewhere in the graph does not prevent an answer
+        depgraph.register_dependency("n", "c");
+        assert_eq!(depgraph.are_independent(&"b", &"m"), Ok(true));
+        assert_eq!(depgraph.are_independent(&"d", &"k"), Ok(true));
+        assert_eq!(depgraph.are_independent(&"m", &"g"), Ok(false));
+    }
+
+    #[test]
+    fn solvent_test_resolve_annotated() {
+        let mut depgraph = stoppage_graph();
+        depgraph
+            .mark_as_satisfied(&["superconn", "owneruser", "database"])
+            .unwrap();
+
+        let annotated = depgraph.resolve_annotated(&"ownerconn").unwrap();
+        assert_eq!(
+            annotated,
+            vec![
+                (&"superconn", NodeStatus::Satisfied),
+                (&"owneruser", NodeStatus::Satisfied),
+                (&"database", NodeStatus::Satisfied),
+                (&"ownerconn", NodeStatus::Pending),
+            ]
+        );
+
+        // The pending nodes are those which resolve outputs, in the same order
+        let annotated = depgraph.resolve_annotated(&"appconn").unwrap();
+        assert_eq!(annotated.len(), 10);
+        let pending: Vec<&&str> = annotated
+            .iter()
+            .filter(|(_, status)| *status == NodeStatus::Pending)
+            .map(|(node, _)| *node)
+            .collect();
+        assert_eq!(pending, depgraph.resolve(&"appconn").unwrap());
+
+        // Nodes only needed through a satisfied node are skipped too
+        let mut depgraph: DepGraph<&str> = DepGraph::new();
+        depgraph.register_dependency("a", "b");
+        depgraph.register_dependency("b", "c");
+        depgraph.mark_as_satisfied(&["b"]).unwrap();
+        assert_eq!(
+            depgraph.resolve_annotated(&"a").unwrap(),
+            vec![
+                (&"c", NodeStatus::Satisfied),
+                (&"b", NodeStatus::Satisfied),
+                (&"a", NodeStatus::Pending),
+            ]
+        );
+        assert_eq!(depgraph.resolve(&"a").unwrap(), vec![&"a"]);
+
+        assert_eq!(
+            depgraph.resolve_annotated(&"nope"),
+            Err(SolventError::NoSuchNode)
+        );
+    }
+
+    #[test]
+    fn solvent_test_satisfied_bitset() {
+        let mut set = BitSet::new();
+        for pos in [130, 3, 64, 63, 0].iter() {
+            assert!(set.insert(*pos));
+            assert!(!set.insert(*pos));
         }
+        assert_eq!(set.len(), 5);
+        assert!(set.contains(&64));
+        assert!(!set.contains(&65));
+        assert!(!set.contains(&100_000));
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![0, 3, 63, 64, 130]);
+        assert_eq!(set, [0, 3, 63, 64, 130].iter().cloned().collect());
+        assert!(BitSet::new().is_empty());
 
-        // Be sure we did not depend on these
-        assert!(!results.contains(&"appuser"));
-        assert!(!results.contains(&"owneruser"));
-        assert!(!results.contains(&"superconn"));
+        // Resolution skips exactly the satisfied nodes, however far apart they are
+        let mut depgraph: DepGraph<usize> = DepGraph::new();
+        depgraph.register_dependencies(0, (1..300).collect());
+        let satisfied: Vec<usize> = (1..300).filter(|n| n % 3 == 0).collect();
+        depgraph.mark_as_satisfied(&satisfied).unwrap();
+        let mut order: Vec<usize> = depgraph.resolve(&0).unwrap().into_iter().cloned().collect();
+        assert_eq!(order.last(), Some(&0));
+        let mut iterated: Vec<usize> = depgraph
+            .dependencies_of(&0)
+            .unwrap()
+            .map(|n| *n.unwrap())
+            .collect();
+        order.sort_unstable();
+        iterated.sort_unstable();
+        let expected: Vec<usize> = (0..300).filter(|n| *n == 0 || n % 3 != 0).collect();
+        assert_eq!(order, expected);
+        assert_eq!(iterated, expected);
+    }
 
-        // Be sure we actually output enough stuff
-        assert!(results.len() == 7);
+    #[test]
+    fn solvent_test_satisfied_bitset_size() {
+        // The satisfied set, and each iterator's copy of it, takes one bit per node
+        let mut depgraph: DepGraph<usize> = DepGraph::new();
+        depgraph.register_nodes((0..1000).collect());
+        let satisfied: Vec<usize> = (0..1000).filter(|n| n % 2 == 0).collect();
+        depgraph.mark_as_satisfied(&satisfied).unwrap();
+        assert_eq!(depgraph.satisfied.word_count(), 16);
 
-        // Be sure each output is unique
-        for result in results.iter() {
-            let mut count: usize = 0;
-            for result2 in results.iter() {
-                if result == result2 {
-                    count += 1;
-                }
+        let iter = depgraph.dependencies_of(&1).unwrap();
+        assert_eq!(iter.satisfied.word_count(), 16);
+    }
+
+    #[test]
+    fn solvent_test_partition() {
+        let mut depgraph: DepGraph<&str> = DepGraph::new();
+        depgraph.register_dependencies("a", vec!["b"]);
+        depgraph.register_dependencies("b", vec!["c"]);
+        depgraph.register_dependencies("x", vec!["y"]);
+
+        let parts = depgraph.partition(2);
+        assert_eq!(parts.len(), 2);
+        for part in parts.iter() {
+            let order = part.topological_sort().unwrap();
+            assert_eq!(order.len(), part.nodes.len());
+            assert!(order == vec![&"c", &"b", &"a"] || order == vec![&"y", &"x"]);
+            // Phantoms stay phantoms
+            assert!(part.phantom_nodes() == vec![&"c"] || part.phantom_nodes() == vec![&"y"]);
+        }
+
+        // The larger component is split, and the part holding "a" treats "c" as done
+        let parts = depgraph.partition(3);
+        assert_eq!(parts.len(), 3);
+        let upper = parts.iter().find(|part| part.nodes.contains(&"a")).unwrap();
+        assert_eq!(upper.resolve(&"a").unwrap(), vec![&"b", &"a"]);
+        let lower = parts.iter().find(|part| part.nodes == vec!["c"]).unwrap();
+        assert_eq!(lower.resolve(&"c").unwrap(), vec![&"c"]);
+
+        // There is never more than one part per node
+        assert_eq!(depgraph.partition(10).len(), 5);
+        assert_eq!(depgraph.partition(usize::MAX).len(), 5);
+        assert_eq!(depgraph.partition(1).len(), 1);
+        assert!(depgraph.partition(0).is_empty());
+    }
+
+    #[test]
+    fn solvent_test_contains_path() {
+        let mut depgraph: DepGraph<&str> = DepGraph::new();
+        depgraph.register_dependencies("a", vec!["b", "c"]);
+        depgraph.register_dependency("b", "d");
+        depgraph.register_dependency("d", "a");
+
+        assert_eq!(depgraph.contains_path(&"a", &"d", 2), Ok(true));
+        assert_eq!(depgraph.contains_path(&"a", &"d", 1), Ok(false));
+        assert_eq!(depgraph.contains_path(&"a", &"a", 0), Ok(true));
+        assert_eq!(depgraph.contains_path(&"d", &"c", 10), Ok(true));
+        assert_eq!(depgraph.contains_path(&"c", &"a", 10), Ok(false));
+        // The search ends once there is nowhere further to go
+        assert_eq!(depgraph.contains_path(&"c", &"a", usize::MAX), Ok(false));
+        assert_eq!(
+            branching_graph().contains_path(&"n", &"a", usize::MAX),
+            Ok(false)
+        );
+        assert_eq!(
+            depgraph.contains_path(&"a", &"z", 1),
+            Err(SolventError::NoSuchNode)
+        );
+    }
+
+    #[test]
+    fn solvent_test_adjacency() {
+        let depgraph = branching_graph();
+        let adjacency = depgraph.adjacency();
+        assert_eq!(adjacency.len(), 14);
+        assert_eq!(adjacency[0], (&"a", vec![&"b", &"c", &"d"]));
+        assert_eq!(adjacency[12], (&"m", vec![&"n"]));
+        assert_eq!(adjacency[13], (&"n", vec![]));
+    }
+
+    #[test]
+    fn solvent_test_resolve_consistent() {
+        let mut depgraph = stoppage_graph();
+        depgraph.mark_as_satisfied(&["superconn"]).unwrap();
+        assert_eq!(
+            depgraph.resolve_consistent(&"database"),
+            depgraph.resolve(&"database")
+        );
+
+        // database is marked done, but owneruser, which it needs, is not
+        depgraph.mark_as_satisfied(&["database"]).unwrap();
+        assert_eq!(
+            depgraph.resolve_consistent(&"adminconn"),
+            Err(SolventError::DependenciesNotSatisfied(
+                "\"database\"".to_string(),
+                "\"owneruser\"".to_string()
+            ))
+        );
+        assert_eq!(depgraph.resolve(&"adminconn").unwrap(), vec![&"adminconn"]);
+
+        // Conflicts outside of what the target needs do not matter
+        assert_eq!(
+            depgraph.resolve_consistent(&"appuser").unwrap(),
+            vec![&"appuser"]
+        );
+        assert_eq!(
+            depgraph.resolve_consistent(&"nope"),
+            Err(SolventError::NoSuchNode)
+        );
+    }
+
+    #[test]
+    fn solvent_test_with_nodes() {
+        let depgraph: DepGraph<&str> = DepGraph::new()
+            .with_node("isolated")
+            .with_nodes(vec!["a", "b", "a"]);
+        assert_eq!(depgraph.nodes, vec!["isolated", "a", "b"]);
+        assert_eq!(
+            depgraph.topological_sort().unwrap(),
+            vec![&"isolated", &"a", &"b"]
+        );
+        assert!(depgraph.phantom_nodes().is_empty());
+    }
+
+    #[cfg(feature = "deterministic")]
+    #[test]
+    fn solvent_test_deterministic_resolution() {
+        // Enough dependencies that they are stored in a hashed set rather than inline
+        let build = || {
+            let mut depgraph: DepGraph<String> = DepGraph::new();
+            let deps: Vec<String> = (0..40).map(|i| format!("dep{}", i)).collect();
+            depgraph.register_dependencies("top".to_string(), deps);
+            for i in 0..40 {
+                depgraph.register_dependency(format!("dep{}", i), format!("leaf{}", i % 7));
             }
-            assert!(count == 1);
+            depgraph.mark_as_satisfied(&["leaf3".to_string()]).unwrap();
+            depgraph
+        };
+
+        let first = build();
+        let expected = first.resolve(&"top".to_string()).unwrap();
+        for _ in 0..100 {
+            let depgraph = build();
+            assert_eq!(depgraph.resolve(&"top".to_string()).unwrap(), expected);
+            let iterated: Vec<&String> = depgraph
+                .dependencies_of(&"top".to_string())
+                .unwrap()
+                .map(|n| n.unwrap())
+                .collect();
+            assert_eq!(iterated, expected);
+        }
+    }
+
+    #[test]
+    fn solvent_test_blast_radius() {
+        let mut depgraph = branching_graph();
+        assert_eq!(depgraph.blast_radius(&"d").unwrap(), vec![&"a", &"b", &"d"]);
+        assert_eq!(
+            depgraph.blast_radius(&"m").unwrap(),
+            vec![&"a", &"c", &"g", &"h", &"i", &"k", &"m"]
+        );
+
+        // A cycle does not prevent the walk from finishing
+        depgraph.register_dependency("d", "a");
+        assert_eq!(depgraph.blast_radius(&"n").unwrap().len(), 10);
+        assert_eq!(depgraph.blast_radius(&"z"), Err(SolventError::NoSuchNode));
+    }
+
+    #[test]
+    fn solvent_test_reduce_to() {
+        let mut depgraph = branching_graph();
+        depgraph.mark_as_satisfied(&["d"]).unwrap();
+
+        let reduced = depgraph.reduce_to(&["b"]).unwrap();
+        assert_eq!(reduced.nodes, vec!["b", "d"]);
+        assert_eq!(reduced.sorted_edges(), vec![(&"b", &"d")]);
+        assert_eq!(reduced.resolve(&"b").unwrap(), vec![&"b"]);
+
+        // Targets which share dependencies keep them once
+        let reduced = depgraph.reduce_to(&["e", "k", "e"]).unwrap();
+        assert_eq!(reduced.nodes, vec!["e", "f", "k", "l", "m", "n"]);
+        assert_eq!(reduced.sorted_edges().len(), 4);
+        assert!(reduced.phantom_nodes().is_empty());
+
+        assert_eq!(
+            depgraph.reduce_to(&["b", "z"]).map(|g| g.nodes),
+            Err(SolventError::NoSuchNode)
+        );
+    }
+
+    #[test]
+    fn solvent_test_validate_integrity() {
+        let depgraph =
+            DepGraph::from_node_and_edge_indices(vec!["a", "b", "c"], vec![(0, 1), (1, 2)])
+                .unwrap();
+        assert_eq!(depgraph.validate_integrity(), Ok(()));
+        assert_eq!(branching_graph().validate_integrity(), Ok(()));
+
+        // Corrupt copies, as a bug might leave them
+        let mut corrupt = depgraph.clone();
+        corrupt.dependencies.get_mut(&1).unwrap().insert(7);
+        assert_eq!(
+            corrupt.validate_integrity(),
+            Err(SolventError::CorruptGraph(
+                "dependency index 7 out of range (3 nodes)".to_string()
+            ))
+        );
+
+        let mut corrupt = depgraph.clone();
+        corrupt.satisfied.insert(3);
+        assert_eq!(
+            corrupt.validate_integrity(),
+            Err(SolventError::CorruptGraph(
+                "satisfied node index 3 out of range (3 nodes)".to_string()
+            ))
+        );
+
+        let mut corrupt = depgraph;
+        corrupt.nodes.pop();
+        assert!(matches!(
+            corrupt.validate_integrity(),
+            Err(SolventError::CorruptGraph(_))
+        ));
+    }
+
+    #[test]
+    fn solvent_test_resolve_enumerated() {
+        let mut depgraph = stoppage_graph();
+        depgraph
+            .mark_as_satisfied(&["superconn", "appuser"])
+            .unwrap();
+
+        let steps = depgraph.resolve_enumerated(&"schemas").unwrap();
+        assert_eq!(steps.len(), 7);
+        for (i, (step, _)) in steps.iter().enumerate() {
+            assert_eq!(*step, i);
         }
+        assert_eq!(steps.last(), Some(&(6, &"schemas")));
+        let nodes: Vec<&&str> = steps.into_iter().map(|(_, node)| node).collect();
+        assert_eq!(nodes, depgraph.resolve(&"schemas").unwrap());
+
+        assert_eq!(
+            depgraph.resolve_enumerated(&"nope"),
+            Err(SolventError::NoSuchNode)
+        );
+    }
+
+    #[test]
+    fn solvent_test_common_dependencies() {
+        let depgraph = branching_graph();
+        assert_eq!(depgraph.common_dependencies(&["a", "b"]), Ok(vec![&"d"]));
+        assert_eq!(
+            depgraph.common_dependencies(&["c", "i"]),
+            Ok(vec![&"j", &"k", &"l", &"m", &"n"])
+        );
+        assert_eq!(
+            depgraph.common_dependencies(&["c", "k", "e"]),
+            Ok(Vec::<&&str>::new())
+        );
+        // b and c share nothing, though a needs both
+        assert!(depgraph
+            .common_dependencies(&["b", "c"])
+            .unwrap()
+            .is_empty());
+        assert_eq!(depgraph.common_dependencies(&["m"]), Ok(vec![&"n"]));
+        assert!(depgraph.common_dependencies(&[]).unwrap().is_empty());
+        assert_eq!(
+            depgraph.common_dependencies(&["a", "z"]),
+            Err(SolventError::NoSuchNode)
+        );
+    }
+
+    #[test]
+    fn solvent_test_scc_order() {
+        let mut depgraph: DepGraph<&str> = DepGraph::new();
+        depgraph.register_dependencies("app", vec!["x", "base"]);
+        depgraph.register_dependency("x", "y");
+        depgraph.register_dependency("y", "z");
+        depgraph.register_dependencies("z", vec!["x", "lib"]);
+        depgraph.register_dependency("lib", "base");
+        depgraph.register_node("alone");
+
+        let components = depgraph.scc_order();
+        assert_eq!(components.len(), 5);
+        let position = |node: &str| {
+            components
+                .iter()
+                .position(|component| component.contains(&&node))
+                .unwrap()
+        };
+        let cycle = position("x");
+        assert_eq!(components[cycle], vec![&"x", &"y", &"z"]);
+        assert!(position("lib") < cycle);
+        assert!(position("base") < position("lib"));
+        assert!(cycle < position("app"));
+        assert_eq!(components[position("alone")], vec![&"alone"]);
+    }
+
+    #[test]
+    fn solvent_test_unblocked_by() {
+        let mut depgraph = stoppage_graph();
+        assert_eq!(
+            depgraph.unblocked_by(&"superconn").unwrap(),
+            vec![&"owneruser", &"appuser"]
+        );
+        // ownerconn also waits on database
+        assert_eq!(
+            depgraph.unblocked_by(&"owneruser").unwrap(),
+            vec![&"database"]
+        );
+
+        depgraph
+            .mark_as_satisfied(&["superconn", "owneruser", "appuser"])
+            .unwrap();
+        assert_eq!(
+            depgraph.unblocked_by(&"database").unwrap(),
+            vec![&"ownerconn", &"adminconn"]
+        );
+        // appconn also waits on schemas
+        assert!(depgraph.unblocked_by(&"appuser").unwrap().is_empty());
+
+        // Satisfied dependents are not reported, and extensions no longer waits on
+        // adminconn
+        depgraph.mark_as_satisfied(&["adminconn"]).unwrap();
+        assert_eq!(
+            depgraph.unblocked_by(&"database").unwrap(),
+            vec![&"ownerconn", &"extensions"]
+        );
+        assert_eq!(
+            depgraph.unblocked_by(&"nope"),
+            Err(SolventError::NoSuchNode)
+        );
     }
 }
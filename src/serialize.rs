@@ -0,0 +1,133 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{DepGraph, HashSet, TopoOrder};
+
+// The serialized form of a DepGraph. Dependencies, satisfied nodes and so on refer to
+// nodes by their index in `nodes`, and are sorted so that the same graph always
+// serializes the same way.
+#[derive(Serialize)]
+#[serde(rename = "DepGraph")]
+struct ReprRef<'a, T> {
+    nodes: &'a [T],
+    dependencies: Vec<(usize, usize)>,
+    satisfied: Vec<usize>,
+    declared: Vec<usize>,
+    tags: Vec<(usize, Vec<&'a String>)>,
+    aliases: &'a [(T, usize)],
+    hints: &'a [(T, T)],
+    acyclic: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "DepGraph")]
+struct Repr<T> {
+    nodes: Vec<T>,
+    dependencies: Vec<(usize, usize)>,
+    satisfied: Vec<usize>,
+    declared: Vec<usize>,
+    tags: Vec<(usize, Vec<String>)>,
+    aliases: Vec<(T, usize)>,
+    hints: Vec<(T, T)>,
+    acyclic: bool,
+}
+
+impl<T: Eq + Serialize> Serialize for DepGraph<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let sorted = |set: &HashSet<usize>| {
+            let mut v: Vec<usize> = set.iter().cloned().collect();
+            v.sort_unstable();
+            v
+        };
+
+        let mut tags: Vec<(usize, Vec<&String>)> = self
+            .tags
+            .iter()
+            .map(|(n, tags)| {
+                let mut tags: Vec<&String> = tags.iter().collect();
+                tags.sort_unstable();
+                (*n, tags)
+            })
+            .collect();
+        tags.sort_unstable();
+
+        ReprRef {
+            nodes: &self.nodes,
+            dependencies: self.edge_index_list(),
+            satisfied: self.satisfied.iter().collect(),
+            declared: sorted(&self.declared),
+            tags,
+            aliases: &self.aliases,
+            hints: &self.hints,
+            acyclic: self.acyclic.is_some(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, T: Eq + Deserialize<'de>> Deserialize<'de> for DepGraph<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = Repr::<T>::deserialize(deserializer)?;
+        DepGraph::from_repr(repr).map_err(D::Error::custom)
+    }
+}
+
+impl<T: Eq> DepGraph<T> {
+    fn from_repr(repr: Repr<T>) -> Result<DepGraph<T>, String> {
+        let len = repr.nodes.len();
+        let check = |n: usize| {
+            if n < len {
+                Ok(n)
+            } else {
+                Err(format!("node index {} out of range", n))
+            }
+        };
+
+        let mut depgraph = DepGraph::new();
+        for node in repr.nodes.into_iter() {
+            if depgraph._pos(&node).is_some() {
+                return Err("duplicate node".to_string());
+            }
+            depgraph.nodes.push(node);
+        }
+        for (node, dep) in repr.dependencies.into_iter() {
+            depgraph._register_dependency(check(node)?, check(dep)?);
+        }
+        for n in repr.satisfied.into_iter() {
+            depgraph.satisfied.insert(check(n)?);
+        }
+        for n in repr.declared.into_iter() {
+            depgraph.declared.insert(check(n)?);
+        }
+        for (n, tags) in repr.tags.into_iter() {
+            depgraph.tags.entry(check(n)?).or_default().extend(tags);
+        }
+        for (alias, n) in repr.aliases.into_iter() {
+            depgraph.aliases.push((alias, check(n)?));
+        }
+        depgraph.hints = repr.hints;
+        if repr.acyclic {
+            let order = depgraph._structural_order().map_err(|e| e.to_string())?;
+            depgraph.acyclic = Some(TopoOrder::from_order(order));
+        }
+        Ok(depgraph)
+    }
+}
+
+#[cfg(feature = "bincode")]
+impl<T: Serialize + serde::de::DeserializeOwned + Eq> DepGraph<T> {
+    /// Serialize the graph, including which nodes are satisfied, into a compact binary
+    /// form with `bincode`.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, crate::SolventError> {
+        bincode::serialize(self).map_err(|e| crate::SolventError::Serialization(e.to_string()))
+    }
+
+    /// Deserialize a graph from the binary form produced by `to_bytes`.
+    pub fn from_bytes(data: &[u8]) -> Result<DepGraph<T>, crate::SolventError> {
+        bincode::deserialize(data).map_err(|e| crate::SolventError::Serialization(e.to_string()))
+    }
+}